@@ -0,0 +1,646 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use chrono::NaiveDate;
+use semver::{Comparator, Op, Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+use crate::date::ISO_DATE_FORMAT;
+use crate::formatter::{BadgeFormatter, LinkFormatter};
+use crate::links::{tag_name, UrlTemplates};
+use crate::version::{is_unreleased, parse_version, ReleaseVersion, VersionScheme};
+use crate::yaml::{Document, Entry, Scope};
+use crate::CategoryType;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+
+    fn color(&self) -> &'static str {
+        match self {
+            Severity::Low => "yellowgreen",
+            Severity::Medium => "yellow",
+            Severity::High => "orange",
+            Severity::Critical => "red",
+        }
+    }
+}
+
+/// The published advisory for a security entry.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Advisory {
+    // Ids are the advisory ids, e.g. `CVE-2024-1234`, `GHSA-xxxx-xxxx-xxxx` or `RUSTSEC-2024-0001`.
+    pub ids: Vec<String>,
+    pub severity: Option<Severity>,
+    // Cvss is the CVSS vector, e.g. `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`.
+    pub cvss: Option<String>,
+    // Package is the affected package. Defaults to the package the entry is listed under.
+    pub package: Option<String>,
+    // Affected is the version requirement of the affected versions, e.g. `>=1.0.0, <1.2.3`.
+    pub affected: Option<String>,
+    // Patched are the version requirements of the fixed versions, e.g. `>=1.2.3` and the backport `~1.1.9`.
+    // Defaults to the release the entry is listed in and all later versions.
+    pub patched: Option<Vec<String>>,
+}
+
+const ADVISORY_URLS: [(&str, &str); 3] = [
+    ("CVE-", "https://www.cve.org/CVERecord?id={id}"),
+    ("GHSA-", "https://github.com/advisories/{id}"),
+    ("RUSTSEC-", "https://rustsec.org/advisories/{id}.html"),
+];
+
+const CVSS_VERSIONS: [(&str, &str); 3] = [("CVSS:3.0/", "CVSS_V3"), ("CVSS:3.1/", "CVSS_V3"), ("CVSS:4.0/", "CVSS_V4")];
+
+/// The page of an advisory id in its database, if the id is of a known database.
+pub fn advisory_url(id: &str) -> Option<String> {
+    ADVISORY_URLS
+        .iter()
+        .find(|(prefix, _)| id.starts_with(prefix))
+        .map(|(_, template)| template.replace("{id}", id))
+}
+
+fn cvss_type(vector: &str) -> Option<&'static str> {
+    CVSS_VERSIONS
+        .iter()
+        .find(|(prefix, _)| vector.starts_with(prefix))
+        .map(|(_, name)| *name)
+}
+
+impl Advisory {
+    /// The problems with the ids, the CVSS vector and the version requirements,
+    /// for the advisory of an entry in the release with the version.
+    pub fn problems(&self, version: &str, scheme: VersionScheme) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.ids.is_empty() {
+            problems.push("advisory has no ids".to_string());
+        }
+        for id in &self.ids {
+            if advisory_url(id).is_none() {
+                problems.push(format!("advisory id '{}' is not a CVE, GHSA or RUSTSEC id", id));
+            }
+        }
+        if let Some(vector) = &self.cvss {
+            if cvss_type(vector).is_none() {
+                problems.push(format!("cvss '{}' must start with CVSS:3.0/, CVSS:3.1/ or CVSS:4.0/", vector));
+            }
+        }
+        for requirement in self.affected.iter().chain(self.patched.iter().flatten()) {
+            if let Err(err) = VersionReq::parse(requirement) {
+                problems.push(format!("'{}' is not a valid version requirement: {}", requirement, err));
+            }
+        }
+        if problems.is_empty() {
+            if let Err(err) = osv_events(self, version, scheme) {
+                problems.push(err);
+            }
+        }
+        problems
+    }
+
+    /// The patched requirements, or the version of the release the entry is listed in and later.
+    pub fn patched_or_default(&self, version: &str, scheme: VersionScheme) -> Result<Vec<String>, String> {
+        match &self.patched {
+            Some(patched) => Ok(patched.clone()),
+            None if is_unreleased(version) => Ok(Vec::new()),
+            None => {
+                // The requirement uses the parsed version, since a `v` prefix or build metadata is not allowed in it.
+                let parsed = match parse_version(version, scheme).map_err(|err| err.to_string())? {
+                    ReleaseVersion::SemVer(mut semver) => {
+                        semver.build = semver::BuildMetadata::EMPTY;
+                        semver.to_string()
+                    }
+                    calver => calver.to_string(),
+                };
+                let requirement = format!(">={}", parsed);
+                VersionReq::parse(&requirement).map_err(|err| {
+                    format!("release '{}' can not be the patched version, set `patched`: {}", version, err)
+                })?;
+                Ok(vec![requirement])
+            }
+        }
+    }
+}
+
+// Shields.io uses dashes as separators, so dashes and underscores in the texts are doubled.
+fn badge_url(label: &str, message: &str, color: &str) -> String {
+    let escape = |text: &str| text.replace('-', "--").replace('_', "__").replace(' ', "_");
+    format!("https://img.shields.io/badge/{}-{}-{}", escape(label), escape(message), color)
+}
+
+/// The advisory ids as links, the severity badge and the affected and patched versions, appended to the entry.
+pub fn advisory_suffix<F: LinkFormatter + BadgeFormatter + ?Sized>(
+    advisory: &Advisory,
+    version: &str,
+    scheme: VersionScheme,
+    formatter: &F,
+) -> String {
+    let mut details: Vec<String> = vec![advisory
+        .ids
+        .iter()
+        .map(|id| match advisory_url(id) {
+            Some(url) => formatter.link(id, &url),
+            None => id.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")];
+    if let Some(affected) = &advisory.affected {
+        details.push(format!("affected: {}", affected));
+    }
+    let patched = advisory.patched_or_default(version, scheme).expect("advisories are validated");
+    if !patched.is_empty() {
+        details.push(format!("patched: {}", patched.join(" or ")));
+    }
+
+    let mut suffix = format!(" ({})", details.join("; "));
+    // The badge links to the CVSS calculator with the vector filled in.
+    let calculator = advisory.cvss.as_ref().map(|vector| {
+        let version = &vector["CVSS:".len()..vector.find('/').unwrap_or(vector.len())];
+        format!("https://www.first.org/cvss/calculator/{}#{}", version, vector)
+    });
+    match (advisory.severity, &calculator) {
+        (Some(severity), _) => {
+            let alt = format!("severity: {}", severity.name());
+            let image = badge_url("severity", severity.name(), severity.color());
+            suffix += &format!(" {}", formatter.badge(&alt, &image, calculator.as_deref()));
+        }
+        (None, Some(calculator)) => {
+            let image = badge_url("cvss", "vector", "lightgrey");
+            suffix += &format!(" {}", formatter.badge("CVSS vector", &image, Some(calculator)));
+        }
+        (None, None) => {}
+    }
+    suffix
+}
+
+/// A security entry with an advisory, and where it is listed.
+pub struct PublishedAdvisory<'a> {
+    pub version: &'a str,
+    pub date: Option<NaiveDate>,
+    pub package: Option<&'a str>,
+    pub entry: &'a Entry,
+    pub advisory: &'a Advisory,
+}
+
+impl PublishedAdvisory<'_> {
+    /// The first line of the entry.
+    pub fn summary(&self) -> &str {
+        self.entry.text.trim().lines().next().unwrap_or_default()
+    }
+}
+
+/// All advisories in the document, newest release first. With `id` only the advisory that has that id.
+pub fn advisories<'a>(document: &'a Document, id: Option<&str>) -> Vec<PublishedAdvisory<'a>> {
+    let mut result = Vec::new();
+    for (version, release) in &document.releases {
+        for (scope, changes) in release.scoped_changes() {
+            for (category, entry) in changes.entries() {
+                let (CategoryType::Security, Some(advisory)) = (category, &entry.advisory) else {
+                    continue;
+                };
+                if id.is_some_and(|id| !advisory.ids.iter().any(|advisory_id| advisory_id == id)) {
+                    continue;
+                }
+                let package = match scope {
+                    Scope::Package(name) => Some(name),
+                    _ => None,
+                };
+                result.push(PublishedAdvisory {
+                    version,
+                    date: release.date,
+                    package: advisory.package.as_deref().or(package),
+                    entry,
+                    advisory,
+                });
+            }
+        }
+    }
+    result
+}
+
+/// A version that starts or ends a range, e.g. `1.2` for `>=1.2`, kept as written and as a comparable version.
+#[derive(Debug, Clone)]
+struct RangeVersion {
+    text: String,
+    version: Version,
+}
+
+impl RangeVersion {
+    fn new(major: u64, minor: Option<u64>, patch: Option<u64>) -> Self {
+        let text = [Some(major), minor, patch]
+            .into_iter()
+            .flatten()
+            .map(|part| part.to_string())
+            .collect::<Vec<_>>()
+            .join(".");
+        RangeVersion {
+            text,
+            version: Version::new(major, minor.unwrap_or(0), patch.unwrap_or(0)),
+        }
+    }
+
+    fn from_comparator(comparator: &Comparator) -> Self {
+        let mut range_version = RangeVersion::new(comparator.major, comparator.minor, comparator.patch);
+        if !comparator.pre.is_empty() {
+            range_version.text += &format!("-{}", comparator.pre);
+            range_version.version.pre = comparator.pre.clone();
+        }
+        range_version
+    }
+}
+
+/// The first version and the first version after the range that a requirement allows.
+/// `^1.2.3` allows `1.2.3` up to `2.0.0`, and `~1.1.9` allows `1.1.9` up to `1.2.0`.
+fn requirement_range(text: &str) -> Result<(Option<RangeVersion>, Option<RangeVersion>), String> {
+    let requirement =
+        VersionReq::parse(text).map_err(|err| format!("'{}' is not a valid version requirement: {}", text, err))?;
+    let mut lower = None;
+    let mut upper = None;
+    for comparator in &requirement.comparators {
+        let (major, minor, patch) = (comparator.major, comparator.minor, comparator.patch);
+        match comparator.op {
+            Op::GreaterEq | Op::Exact => lower = Some(RangeVersion::from_comparator(comparator)),
+            Op::Less => upper = Some(RangeVersion::from_comparator(comparator)),
+            Op::Caret => {
+                lower = Some(RangeVersion::from_comparator(comparator));
+                upper = Some(match (major, minor, patch) {
+                    (0, Some(0), Some(patch)) => RangeVersion::new(0, Some(0), Some(patch + 1)),
+                    (0, Some(minor), _) => RangeVersion::new(0, Some(minor + 1), Some(0)),
+                    _ => RangeVersion::new(major + 1, Some(0), Some(0)),
+                });
+            }
+            Op::Tilde => {
+                lower = Some(RangeVersion::from_comparator(comparator));
+                upper = Some(match minor {
+                    Some(minor) => RangeVersion::new(major, Some(minor + 1), Some(0)),
+                    None => RangeVersion::new(major + 1, Some(0), Some(0)),
+                });
+            }
+            _ => return Err(format!("'{}' can not be written as a range, use `>=`, `<`, `=`, `^` or `~`", text)),
+        }
+    }
+    Ok((lower, upper))
+}
+
+/// The OSV events of an advisory: introduced at the start of the affected range, and fixed and introduced again
+/// at the start and the end of each patched range.
+fn osv_events(advisory: &Advisory, version: &str, scheme: VersionScheme) -> Result<Vec<OsvEvent>, String> {
+    let introduced = match advisory.affected.as_deref() {
+        Some(affected) => requirement_range(affected)?.0.map(|lower| lower.text),
+        None => None,
+    };
+    let mut events = vec![OsvEvent::Introduced(introduced.unwrap_or_else(|| "0".to_string()))];
+
+    let mut patched: Vec<(RangeVersion, Option<RangeVersion>)> = Vec::new();
+    for requirement in advisory.patched_or_default(version, scheme)? {
+        match requirement_range(&requirement)? {
+            (Some(lower), upper) => patched.push((lower, upper)),
+            (None, _) => return Err(format!("patched requirement '{}' has no lower bound", requirement)),
+        }
+    }
+    patched.sort_by(|(a, _), (b, _)| a.version.cmp(&b.version));
+
+    // Overlapping patched ranges are merged, so the events alternate between fixed and introduced.
+    let mut merged: Vec<(RangeVersion, Option<RangeVersion>)> = Vec::new();
+    for (lower, upper) in patched {
+        match merged.last_mut() {
+            Some((_, last_upper)) if last_upper.as_ref().is_none_or(|last| last.version >= lower.version) => {
+                let extends = match (&last_upper, &upper) {
+                    (None, _) => false,
+                    (Some(_), None) => true,
+                    (Some(last), Some(upper)) => upper.version > last.version,
+                };
+                if extends {
+                    *last_upper = upper;
+                }
+            }
+            _ => merged.push((lower, upper)),
+        }
+    }
+    for (lower, upper) in merged {
+        events.push(OsvEvent::Fixed(lower.text));
+        if let Some(upper) = upper {
+            events.push(OsvEvent::Introduced(upper.text));
+        }
+    }
+    Ok(events)
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OsvEvent {
+    Introduced(String),
+    Fixed(String),
+}
+
+#[derive(Debug, Serialize)]
+pub struct OsvRange {
+    #[serde(rename = "type")]
+    pub range_type: &'static str,
+    pub events: Vec<OsvEvent>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OsvPackage {
+    pub ecosystem: &'static str,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OsvAffected {
+    pub package: OsvPackage,
+    pub ranges: Vec<OsvRange>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OsvSeverity {
+    #[serde(rename = "type")]
+    pub severity_type: &'static str,
+    pub score: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OsvReference {
+    #[serde(rename = "type")]
+    pub reference_type: &'static str,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OsvDatabaseSpecific {
+    pub severity: String,
+}
+
+/// An advisory in the Open Source Vulnerability format, see https://ossf.github.io/osv-schema/
+#[derive(Debug, Serialize)]
+pub struct OsvRecord {
+    pub schema_version: &'static str,
+    pub id: String,
+    pub modified: String,
+    pub published: String,
+    pub aliases: Vec<String>,
+    pub summary: String,
+    pub details: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub severity: Vec<OsvSeverity>,
+    pub affected: Vec<OsvAffected>,
+    pub references: Vec<OsvReference>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database_specific: Option<OsvDatabaseSpecific>,
+}
+
+const OSV_SCHEMA_VERSION: &str = "1.6.0";
+
+// Packages are linked to crates.io, so that is the ecosystem of the advisories too.
+const ECOSYSTEM: &str = "crates.io";
+
+fn missing_package(published: &PublishedAdvisory) -> String {
+    format!(
+        "advisory {} in {} needs a package, list it under `packages` or set `package`",
+        published.advisory.ids.join(", "),
+        published.version
+    )
+}
+
+/// The OSV record of an advisory. Unreleased advisories are dated `today`.
+pub fn osv_record(
+    published: &PublishedAdvisory,
+    document: &Document,
+    urls: &UrlTemplates,
+    today: NaiveDate,
+) -> Result<OsvRecord, String> {
+    let advisory = published.advisory;
+    let package = published.package.ok_or_else(|| missing_package(published))?;
+    let timestamp = format!("{}T00:00:00Z", published.date.unwrap_or(today).format(ISO_DATE_FORMAT));
+    let scheme = document.versioning.unwrap_or_default();
+    let range_type = match scheme {
+        VersionScheme::SemVer => "SEMVER",
+        VersionScheme::CalVer => "ECOSYSTEM",
+    };
+
+    let mut references: Vec<OsvReference> = advisory
+        .ids
+        .iter()
+        .filter_map(|id| advisory_url(id))
+        .map(|url| OsvReference {
+            reference_type: "ADVISORY",
+            url,
+        })
+        .collect();
+    if !is_unreleased(published.version) {
+        let tag = tag_name(document.tag.as_deref(), published.version);
+        references.push(OsvReference {
+            reference_type: "WEB",
+            url: urls.release(&document.repo, &tag),
+        });
+    }
+
+    Ok(OsvRecord {
+        schema_version: OSV_SCHEMA_VERSION,
+        id: advisory.ids[0].clone(),
+        modified: timestamp.clone(),
+        published: timestamp,
+        aliases: advisory.ids[1..].to_vec(),
+        summary: published.summary().to_string(),
+        details: published.entry.text.trim().to_string(),
+        severity: advisory
+            .cvss
+            .iter()
+            .filter_map(|vector| {
+                cvss_type(vector).map(|severity_type| OsvSeverity {
+                    severity_type,
+                    score: vector.clone(),
+                })
+            })
+            .collect(),
+        affected: vec![OsvAffected {
+            package: OsvPackage {
+                ecosystem: ECOSYSTEM,
+                name: package.to_string(),
+            },
+            ranges: vec![OsvRange {
+                range_type,
+                events: osv_events(advisory, published.version, scheme)?,
+            }],
+        }],
+        references,
+        database_specific: advisory.severity.map(|severity| OsvDatabaseSpecific {
+            severity: severity.name().to_uppercase(),
+        }),
+    })
+}
+
+// A JSON string is also a valid TOML basic string.
+fn toml_string(text: &str) -> String {
+    serde_json::to_string(text).expect("strings can always be serialized")
+}
+
+fn toml_array(items: &[String]) -> String {
+    let quoted: Vec<String> = items.iter().map(|item| toml_string(item)).collect();
+    format!("[{}]", quoted.join(", "))
+}
+
+/// A draft of the advisory for the RustSec advisory database, the TOML front matter followed by the description.
+/// The id is a placeholder unless the advisory already has a RUSTSEC id.
+pub fn rustsec_draft(published: &PublishedAdvisory, scheme: VersionScheme, today: NaiveDate) -> Result<String, String> {
+    let advisory = published.advisory;
+    let package = published.package.ok_or_else(|| missing_package(published))?;
+    let id = advisory
+        .ids
+        .iter()
+        .find(|id| id.starts_with("RUSTSEC-"))
+        .cloned()
+        .unwrap_or_else(|| "RUSTSEC-0000-0000".to_string());
+    let aliases: Vec<String> = advisory.ids.iter().filter(|alias| **alias != id).cloned().collect();
+    let unaffected: Vec<String> = match advisory.affected.as_deref() {
+        Some(affected) => requirement_range(affected)?.0.map(|lower| format!("<{}", lower.text)).into_iter().collect(),
+        None => Vec::new(),
+    };
+
+    let mut lines = vec![
+        "```toml".to_string(),
+        "[advisory]".to_string(),
+        format!("id = {}", toml_string(&id)),
+        format!("package = {}", toml_string(package)),
+        format!("date = {}", toml_string(&published.date.unwrap_or(today).format(ISO_DATE_FORMAT).to_string())),
+    ];
+    if let Some(url) = aliases.first().and_then(|alias| advisory_url(alias)) {
+        lines.push(format!("url = {}", toml_string(&url)));
+    }
+    lines.push("categories = []".to_string());
+    lines.push("keywords = []".to_string());
+    lines.push(format!("aliases = {}", toml_array(&aliases)));
+    if let Some(vector) = &advisory.cvss {
+        lines.push(format!("cvss = {}", toml_string(vector)));
+    }
+    lines.push(String::new());
+    lines.push("[versions]".to_string());
+    lines.push(format!("patched = {}", toml_array(&advisory.patched_or_default(published.version, scheme)?)));
+    lines.push(format!("unaffected = {}", toml_array(&unaffected)));
+    lines.push("```".to_string());
+    lines.push(String::new());
+    lines.push(format!("# {}", published.summary()));
+    lines.push(String::new());
+    lines.push(published.entry.text.trim().to_string());
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn advisory(affected: Option<&str>, patched: Option<&[&str]>) -> Advisory {
+        Advisory {
+            ids: vec!["CVE-2024-1234".to_string()],
+            affected: affected.map(str::to_string),
+            patched: patched.map(|patched| {
+                patched
+                    .iter()
+                    .map(|requirement| requirement.to_string())
+                    .collect()
+            }),
+            ..Advisory::default()
+        }
+    }
+
+    fn texts(
+        range: (Option<RangeVersion>, Option<RangeVersion>),
+    ) -> (Option<String>, Option<String>) {
+        (
+            range.0.map(|lower| lower.text),
+            range.1.map(|upper| upper.text),
+        )
+    }
+
+    #[test]
+    fn requirement_ranges() {
+        let range = |requirement| texts(requirement_range(requirement).unwrap());
+        assert_eq!(range(">=1.2.3"), (Some("1.2.3".to_string()), None));
+        assert_eq!(
+            range(">=1.0.0, <1.2.3"),
+            (Some("1.0.0".to_string()), Some("1.2.3".to_string()))
+        );
+        assert_eq!(
+            range("^1.2.3"),
+            (Some("1.2.3".to_string()), Some("2.0.0".to_string()))
+        );
+        assert_eq!(
+            range("^0.2"),
+            (Some("0.2".to_string()), Some("0.3.0".to_string()))
+        );
+        assert_eq!(
+            range("~1.1.9"),
+            (Some("1.1.9".to_string()), Some("1.2.0".to_string()))
+        );
+        assert_eq!(range("<1.0"), (None, Some("1.0".to_string())));
+        assert!(requirement_range(">1.0").is_err());
+        assert!(requirement_range("not a requirement").is_err());
+    }
+
+    #[test]
+    fn events_of_patched_ranges() {
+        let events = osv_events(
+            &advisory(Some(">=1.0.0, <1.2.3"), Some(&["~1.1.9", ">=1.2.3"])),
+            "1.2.3",
+            VersionScheme::SemVer,
+        );
+        assert_eq!(
+            events.unwrap(),
+            vec![
+                OsvEvent::Introduced("1.0.0".to_string()),
+                OsvEvent::Fixed("1.1.9".to_string()),
+                OsvEvent::Introduced("1.2.0".to_string()),
+                OsvEvent::Fixed("1.2.3".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn default_patched_version_is_parsed() {
+        let events = osv_events(
+            &advisory(None, None),
+            "v1.2.3+build.5",
+            VersionScheme::SemVer,
+        );
+        assert_eq!(
+            events.unwrap(),
+            vec![
+                OsvEvent::Introduced("0".to_string()),
+                OsvEvent::Fixed("1.2.3".to_string())
+            ]
+        );
+        assert_eq!(
+            advisory(None, None).patched_or_default("v1.2.3", VersionScheme::SemVer),
+            Ok(vec![">=1.2.3".to_string()])
+        );
+    }
+
+    #[test]
+    fn patched_requirement_without_lower_bound_fails() {
+        assert!(osv_events(
+            &advisory(None, Some(&["<1.0"])),
+            "1.2.3",
+            VersionScheme::SemVer
+        )
+        .is_err());
+        assert!(!advisory(None, Some(&["<1.0"]))
+            .problems("1.2.3", VersionScheme::SemVer)
+            .is_empty());
+    }
+}
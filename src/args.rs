@@ -0,0 +1,124 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use std::collections::{HashMap, HashSet};
+
+// Options that take a value, either as `--name value` or `--name=value`.
+const VALUE_OPTIONS: &[&str] = &[
+    "file",
+    "format",
+    "date-format",
+    "locale",
+    "git",
+    "abbrev",
+    "base",
+    "head",
+    "text",
+    "regex",
+    "category",
+    "since",
+    "until",
+    "after",
+    "before",
+    "version",
+    "section",
+    "package",
+    "repo",
+    "author",
+    "pr",
+    "max-releases",
+    "id",
+    "dir",
+];
+
+// Options without a value.
+const FLAGS: &[&str] = &[
+    "compare-links",
+    "omit-unreleased",
+    "sort",
+    "latest",
+    "include-yanked",
+    "no-heading",
+    "allow-history-changes",
+    "strict",
+];
+
+pub struct Args {
+    pub positional: Vec<String>,
+    options: HashMap<String, String>,
+    flags: HashSet<String>,
+}
+
+impl Args {
+    /// Fails for an option that is not known, so a misspelled flag is not silently ignored.
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Args, String> {
+        let mut result = Args {
+            positional: Vec::new(),
+            options: HashMap::new(),
+            flags: HashSet::new(),
+        };
+
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                result.positional.push(arg);
+                continue;
+            };
+
+            if let Some((name, value)) = name.split_once('=') {
+                if !VALUE_OPTIONS.contains(&name) {
+                    return Err(match FLAGS.contains(&name) {
+                        true => format!("option '--{}' does not take a value", name),
+                        false => format!("unknown option '--{}'", name),
+                    });
+                }
+                result.options.insert(name.to_string(), value.to_string());
+            } else if VALUE_OPTIONS.contains(&name) {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("option '--{}' requires a value", name))?;
+                result.options.insert(name.to_string(), value);
+            } else if FLAGS.contains(&name) {
+                result.flags.insert(name.to_string());
+            } else {
+                return Err(format!("unknown option '--{}'", name));
+            }
+        }
+
+        Ok(result)
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.contains(name)
+    }
+
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn options_and_flags() {
+        let args = parse(&["check-history", "--base", "main", "--file=a.yaml", "--allow-history-changes"]).unwrap();
+        assert_eq!(args.positional, vec!["check-history"]);
+        assert_eq!(args.option("base"), Some("main"));
+        assert_eq!(args.option("file"), Some("a.yaml"));
+        assert!(args.flag("allow-history-changes"));
+    }
+
+    #[test]
+    fn unknown_options_are_rejected() {
+        assert!(parse(&["check-history", "--allow-history-change"]).is_err());
+        assert!(parse(&["--fiel=a.yaml"]).is_err());
+        assert!(parse(&["--sort=yes"]).is_err());
+        assert!(parse(&["--base"]).is_err());
+    }
+}
@@ -0,0 +1,252 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::links::{expand, UrlTemplates};
+use crate::release_tags::{indentation, release_key_line, unquoted_key};
+
+/// A downloadable file of a release.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Asset {
+    // Name is the file name, which may use `{version}`, e.g. `nimble-{version}-linux-x64.tar.gz`.
+    pub name: String,
+    // Url is the download url template, using `{repo}`, `{tag}`, `{version}` and `{name}`.
+    // Defaults to the `download` url of the forge.
+    pub url: Option<String>,
+    // Size is the file size in bytes.
+    pub size: Option<u64>,
+    // Sha256 is the hex encoded SHA-256 checksum of the file.
+    pub sha256: Option<String>,
+}
+
+impl Asset {
+    pub fn file_name(&self, version: &str) -> String {
+        expand(&self.name, &[("version", version)])
+    }
+
+    /// The download url of the asset in the release with the version and the tag.
+    pub fn url(&self, repo: &str, urls: &UrlTemplates, version: &str, tag: &str) -> String {
+        let name = self.file_name(version);
+        match &self.url {
+            Some(template) => expand(template, &[("repo", repo), ("tag", tag), ("version", version), ("name", &name)]),
+            None => urls.download(repo, tag, &name),
+        }
+    }
+}
+
+pub fn is_sha256(text: &str) -> bool {
+    text.len() == 64 && text.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// The size with a binary unit, e.g. `1.5 MiB`.
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// The size and the hex encoded SHA-256 checksum of a local file.
+pub fn file_checksum(path: &Path) -> io::Result<(u64, String)> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0; 64 * 1024];
+    let mut size = 0;
+    loop {
+        let count = file.read(&mut buffer)?;
+        if count == 0 {
+            break;
+        }
+        hasher.update(&buffer[..count]);
+        size += count as u64;
+    }
+    Ok((size, format!("{:x}", hasher.finalize())))
+}
+
+/// The computed size and checksum of an asset, by the `name` as it is written in the document.
+pub struct AssetChecksum {
+    pub name: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+fn is_content(line: &str) -> bool {
+    !line.trim().is_empty() && !line.trim_start().starts_with('#')
+}
+
+fn unquoted_value(line: &str) -> &str {
+    let (_, value) = line.split_once(':').unwrap_or_default();
+    let (value, _comment) = value.split_once(" #").unwrap_or((value, ""));
+    value.trim().trim_matches(|c| c == '\'' || c == '"')
+}
+
+/// Sets the `size` and `sha256` of the assets of a release in the yaml text, adding the keys that are missing.
+/// The rest of the text, including comments, is kept as it is.
+pub fn fill_asset_checksums(yaml: &str, release_name: &str, checksums: &[AssetChecksum]) -> Result<String, String> {
+    let mut lines: Vec<String> = yaml.lines().map(str::to_string).collect();
+    let (key_line, release_indentation) = release_key_line(&lines, release_name)?;
+    let release_end = (key_line + 1..lines.len())
+        .find(|index| is_content(&lines[*index]) && indentation(&lines[*index]) <= release_indentation)
+        .unwrap_or(lines.len());
+
+    let assets_line = (key_line + 1..release_end)
+        .find(|index| unquoted_key(&lines[*index]) == Some("assets"))
+        .ok_or(format!("release '{}' has no assets", release_name))?;
+    if !unquoted_value(&lines[assets_line]).is_empty() {
+        return Err(format!("the assets of release '{}' are written inline and can not be filled", release_name));
+    }
+
+    // Each item starts with a dash, and its keys are indented to the text after the dash.
+    let mut items: Vec<(usize, usize)> = Vec::new();
+    let mut item_indentation = None;
+    for (index, line) in lines.iter().enumerate().take(release_end).skip(assets_line + 1) {
+        if !is_content(line) {
+            continue;
+        }
+        let line_indentation = indentation(line);
+        let dash_indentation = *item_indentation.get_or_insert(line_indentation);
+        if line_indentation < dash_indentation {
+            break;
+        }
+        if line_indentation == dash_indentation {
+            if !line.trim_start().starts_with('-') {
+                break;
+            }
+            items.push((index, index + 1));
+        } else if let Some(item) = items.last_mut() {
+            item.1 = index + 1;
+        }
+    }
+
+    let mut filled = Vec::new();
+    for (start, end) in items.into_iter().rev() {
+        let dash_line = &lines[start];
+        let after_dash = dash_line.trim_start()[1..].trim_start();
+        if after_dash.starts_with('{') {
+            return Err(format!("an asset of release '{}' is written inline and can not be filled", release_name));
+        }
+        // The first key is either on the line of the dash or on the next line.
+        let key_indentation = match after_dash.is_empty() {
+            true => (start + 1..end).find(|index| is_content(&lines[*index])).map(|index| indentation(&lines[index])),
+            false => Some(dash_line.len() - after_dash.len()),
+        };
+        let Some(key_indentation) = key_indentation else {
+            continue;
+        };
+        let key_of = |index: usize| -> Option<&str> {
+            let line = &lines[index];
+            match index == start {
+                true => unquoted_key(after_dash),
+                false => (indentation(line) == key_indentation).then(|| unquoted_key(line)).flatten(),
+            }
+        };
+
+        let Some(name_line) = (start..end).find(|index| key_of(*index) == Some("name")) else {
+            continue;
+        };
+        let name = unquoted_value(&lines[name_line]).to_string();
+        let Some(checksum) = checksums.iter().find(|checksum| checksum.name == name) else {
+            continue;
+        };
+
+        let size_line = (start..end).find(|index| key_of(*index) == Some("size"));
+        let sha256_line = (start..end).find(|index| key_of(*index) == Some("sha256"));
+        let mut insert_at = end;
+        for (line, key, value) in [
+            (size_line, "size", checksum.size.to_string()),
+            (sha256_line, "sha256", checksum.sha256.clone()),
+        ] {
+            let field = format!("{}: {}", key, value);
+            match line {
+                Some(index) => {
+                    let prefix_length = match index == start {
+                        true => key_indentation,
+                        false => indentation(&lines[index]),
+                    };
+                    lines[index] = format!("{}{}", &lines[index][..prefix_length], field);
+                }
+                None => {
+                    lines.insert(insert_at, format!("{}{}", " ".repeat(key_indentation), field));
+                    insert_at += 1;
+                }
+            }
+        }
+        filled.push(name);
+    }
+
+    if let Some(missing) = checksums.iter().find(|checksum| !filled.contains(&checksum.name)) {
+        return Err(format!("asset '{}' of release '{}' is not found", missing.name, release_name));
+    }
+
+    let mut result = lines.join("\n");
+    if yaml.ends_with('\n') {
+        result.push('\n');
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    fn checksum(name: &str) -> AssetChecksum {
+        AssetChecksum {
+            name: name.to_string(),
+            size: 1024,
+            sha256: SHA256.to_string(),
+        }
+    }
+
+    #[test]
+    fn missing_size_and_sha256_are_added() {
+        let yaml = concat!(
+            "releases:\n",
+            "  '1.0.0':\n",
+            "    assets:\n",
+            "      - name: 'nimble-{version}.tar.gz' # linux\n",
+            "        url: x\n",
+        );
+        let filled = fill_asset_checksums(yaml, "1.0.0", &[checksum("nimble-{version}.tar.gz")]).unwrap();
+        assert_eq!(filled, format!("{}        size: 1024\n        sha256: {}\n", yaml, SHA256));
+    }
+
+    #[test]
+    fn existing_values_are_replaced() {
+        let yaml = concat!(
+            "releases:\n",
+            "  v1.0.0:\n",
+            "    assets:\n",
+            "      -\n",
+            "        name: \"a.zip\"\n",
+            "        size: 1 # old\n",
+            "      - name: b.zip\n",
+        );
+        let expected = yaml.replace("size: 1 # old", &format!("size: 1024\n        sha256: {}", SHA256));
+        assert_eq!(fill_asset_checksums(yaml, "v1.0.0", &[checksum("a.zip")]).unwrap(), expected);
+    }
+
+    #[test]
+    fn unknown_assets_and_inline_assets_fail() {
+        let yaml = "releases:\n  v1.0.0:\n    assets:\n      - name: a.zip\n";
+        assert!(fill_asset_checksums(yaml, "v1.0.0", &[checksum("b.zip")]).is_err());
+        let inline = "releases:\n  v1.0.0:\n    assets: [{ name: a.zip }]\n";
+        assert!(fill_asset_checksums(inline, "v1.0.0", &[checksum("a.zip")]).is_err());
+    }
+}
@@ -0,0 +1,484 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use regex::{Captures, Regex};
+use serde::Deserialize;
+
+use crate::formatter::LinkFormatter;
+use crate::git::VerifiedCommits;
+use crate::links::{expand, UrlTemplates};
+use crate::yaml::Document;
+
+// An optional qualifier, either a key in `repos` or an `owner/name` slug, e.g. `clog#12` or `piot/clog$abc123`.
+// The qualifier must start at a word boundary, and a word that is neither a key nor a slug, like `PR` in `PR#12`,
+// is not a qualifier. The regex crate has no lookbehind, so the character before the qualifier is part of the match.
+const QUALIFIER: &str = r"(?:(?:^|[^\w/.-])(?P<repo>[\w.-]+(?:/[\w.-]+)?))?";
+const ISSUE: &str = r"##(?P<id>\d+)";
+const PULL_REQUEST: &str = r"#(?P<id>\d+)";
+const COMMIT: &str = r"\$(?P<id>[a-f\d]+)";
+const PROFILE: &str = r"@(?P<id>[\w-]+)";
+
+/// User defined autolink reference, e.g. `NET-1234` for a Jira issue.
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct Autolink {
+    // Prefix is followed by a number, e.g. `NET-` matches `NET-1234` with `{id}` set to `1234`.
+    pub prefix: Option<String>,
+
+    // Pattern is a regular expression. `{id}` is the capture group named `id`, or the complete match.
+    pub pattern: Option<String>,
+
+    // Url is the link template, e.g. `https://example.atlassian.net/browse/NET-{id}`.
+    pub url: String,
+}
+
+pub struct AutolinkRule {
+    regex: Regex,
+    url: String,
+}
+
+impl Autolink {
+    pub fn compile(&self) -> Result<AutolinkRule, String> {
+        let pattern = match (&self.prefix, &self.pattern) {
+            (Some(prefix), None) => format!(r"\b{}(?P<id>\d+)\b", regex::escape(prefix)),
+            (None, Some(pattern)) => pattern.clone(),
+            _ => return Err("autolink must have either a prefix or a pattern".to_string()),
+        };
+
+        let regex = Regex::new(&pattern)
+            .map_err(|err| format!("autolink pattern '{}' is invalid: {}", pattern, err))?;
+
+        Ok(AutolinkRule {
+            regex,
+            url: self.url.clone(),
+        })
+    }
+}
+
+pub fn compile_autolinks(autolinks: &[Autolink]) -> Result<Vec<AutolinkRule>, String> {
+    autolinks.iter().map(Autolink::compile).collect()
+}
+
+/// A repository that qualified references can refer to.
+pub struct RepoLinks {
+    // Repo is the `owner/name` slug of the repository.
+    pub repo: String,
+    pub urls: UrlTemplates,
+}
+
+/// Everything needed to turn references in a line into links.
+pub struct LinkContext<'a> {
+    // Repo is the repository that unqualified `#12`, `##12` and `$abc123` refer to.
+    pub repo: &'a str,
+    pub urls: &'a UrlTemplates,
+    pub autolinks: &'a [AutolinkRule],
+    // Repos are the entries in `repos` of the document, by key.
+    pub repos: &'a HashMap<String, RepoLinks>,
+    // Commits are the full hashes of the commits verified in a local clone.
+    pub commits: &'a VerifiedCommits,
+    // Commit abbreviation is the number of hex digits shown for each commit hash.
+    pub commit_abbreviation: Option<usize>,
+}
+
+/// Owns everything the link contexts of a document refer to.
+pub struct DocumentLinks<'a> {
+    repo: String,
+    urls: UrlTemplates,
+    autolinks: Vec<AutolinkRule>,
+    repos: HashMap<String, RepoLinks>,
+    commits: Cow<'a, VerifiedCommits>,
+    commit_abbreviation: Option<usize>,
+}
+
+impl<'a> DocumentLinks<'a> {
+    pub fn new(document: &Document, commits: &'a VerifiedCommits, commit_abbreviation: Option<usize>) -> Self {
+        Self::with_commits(document, Cow::Borrowed(commits), commit_abbreviation)
+    }
+
+    /// Links for a document without a local clone, so commit hashes are shown as they are written.
+    pub fn without_commits(document: &Document) -> Self {
+        Self::with_commits(document, Cow::Owned(VerifiedCommits::default()), None)
+    }
+
+    fn with_commits(document: &Document, commits: Cow<'a, VerifiedCommits>, commit_abbreviation: Option<usize>) -> Self {
+        let forge = document.forge.clone().unwrap_or_default();
+        let urls = forge.url_templates().with_overrides(document.urls.as_ref());
+        let autolinks = compile_autolinks(document.autolinks.as_deref().unwrap_or_default())
+            .expect("autolinks are validated");
+
+        let mut repos: HashMap<String, RepoLinks> = document
+            .repos
+            .iter()
+            .flatten()
+            .map(|(key, info)| {
+                let repo_urls = match &info.forge {
+                    Some(repo_forge) => repo_forge.url_templates(),
+                    None => urls.clone(),
+                };
+                (key.clone(), RepoLinks { repo: info.repo.clone(), urls: repo_urls })
+            })
+            .collect();
+        // The document repository is added by its slug, so `piot/nimble#2` works from within any of the repos.
+        repos.insert(
+            document.repo.clone(),
+            RepoLinks {
+                repo: document.repo.clone(),
+                urls: urls.clone(),
+            },
+        );
+
+        DocumentLinks {
+            repo: document.repo.clone(),
+            urls,
+            autolinks,
+            repos,
+            commits,
+            commit_abbreviation,
+        }
+    }
+
+    fn context<'s>(&'s self, repo: &'s str, urls: &'s UrlTemplates) -> LinkContext<'s> {
+        LinkContext {
+            repo,
+            urls,
+            autolinks: &self.autolinks,
+            repos: &self.repos,
+            commits: &self.commits,
+            commit_abbreviation: self.commit_abbreviation,
+        }
+    }
+
+    /// Links for the references in the document repository.
+    pub fn document(&self) -> LinkContext<'_> {
+        self.context(&self.repo, &self.urls)
+    }
+
+    /// Links for the references in an entry in `repos`.
+    pub fn repo(&self, key: &str) -> Option<LinkContext<'_>> {
+        self.repos
+            .get(key)
+            .map(|repo_links| self.context(&repo_links.repo, &repo_links.urls))
+    }
+}
+
+impl LinkContext<'_> {
+    /// Finds the repository and its url templates for an optional qualifier.
+    /// A slug that is not in `repos` is assumed to be on the same forge as the current repository.
+    fn resolve<'s>(&'s self, qualifier: Option<&'s str>) -> Option<(&'s str, &'s UrlTemplates)> {
+        let Some(qualifier) = qualifier else {
+            return Some((self.repo, self.urls));
+        };
+
+        if let Some(repo_links) = self.repos.get(qualifier) {
+            return Some((&repo_links.repo, &repo_links.urls));
+        }
+
+        let urls = self
+            .repos
+            .values()
+            .find(|repo_links| repo_links.repo == qualifier)
+            .map_or(self.urls, |repo_links| &repo_links.urls);
+        Some((qualifier, urls))
+    }
+}
+
+/// The qualifier of a reference, if the word before it is a key in `repos` or an `owner/name` slug.
+fn qualifier<'t, F: Fn(&str) -> bool>(captures: &Captures<'t>, is_known_key: F) -> Option<&'t str> {
+    captures
+        .name("repo")
+        .map(|m| m.as_str())
+        .filter(|qualifier| qualifier.contains('/') || is_known_key(qualifier))
+}
+
+// Number of single character edits between two words, where swapping two neighbouring characters is one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    distances[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = distances[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = substitution.min(distances[i - 1][j] + 1).min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+// Words that are written right before a reference without naming a repository, like `PR#12` or `US$100`.
+const PLAIN_WORDS: [&str; 3] = ["PR", "GH", "US"];
+
+/// Returns the words before references in the line that are neither a key in `repos` nor an `owner/name` slug,
+/// each with the key it is close to, if any, e.g. `clgo` in `clgo#12` for the key `clog`.
+pub fn unknown_qualifiers<'t, 'k>(line: &'t str, keys: &[&'k str]) -> Vec<(&'t str, Option<&'k str>)> {
+    let mut unknown = Vec::new();
+    for pattern in [ISSUE, PULL_REQUEST, COMMIT] {
+        let regex = Regex::new(&format!("{}{}", QUALIFIER, pattern)).unwrap();
+        for captures in regex.captures_iter(line) {
+            let Some(word) = captures.name("repo").map(|m| m.as_str()) else {
+                continue;
+            };
+            if word.contains('/')
+                || keys.contains(&word)
+                || PLAIN_WORDS.iter().any(|plain| plain.eq_ignore_ascii_case(word))
+                || unknown.iter().any(|(known, _)| *known == word)
+            {
+                continue;
+            }
+            let close_key = keys
+                .iter()
+                .find(|key| edit_distance(&word.to_lowercase(), &key.to_lowercase()) <= (key.len() / 3).max(1));
+            unknown.push((word, close_key.copied()));
+        }
+    }
+    unknown
+}
+
+/// Returns the optional qualifier and the hash of each commit reference in the line.
+pub fn commit_references<F: Fn(&str) -> bool>(line: &str, is_known_key: F) -> Vec<(Option<&str>, &str)> {
+    let regex = Regex::new(&format!("{}{}", QUALIFIER, COMMIT)).unwrap();
+    regex
+        .captures_iter(line)
+        .map(|captures| (qualifier(&captures, &is_known_key), captures.name("id").unwrap().as_str()))
+        .collect()
+}
+
+/// Returns the word before and the number of each pull request reference in the line.
+/// A number too large to be a pull request is skipped, like it is left unlinked when rendering.
+pub fn pull_request_references(line: &str) -> Vec<(Option<&str>, usize)> {
+    // `##12` is an issue, so the hashes are captured to tell them apart.
+    let regex = Regex::new(&format!(r"{}(?P<hashes>##?)(?P<id>\d+)", QUALIFIER)).unwrap();
+    regex
+        .captures_iter(line)
+        .filter(|captures| &captures["hashes"] == "#")
+        .filter_map(|captures| {
+            let qualifier = captures.name("repo").map(|m| m.as_str());
+            captures["id"].parse().ok().map(|number| (qualifier, number))
+        })
+        .collect()
+}
+
+/// Returns the user of each `@user` mention in the line.
+pub fn mentions(line: &str) -> Vec<&str> {
+    let regex = Regex::new(PROFILE).unwrap();
+    regex
+        .captures_iter(line)
+        .map(|captures| captures.name("id").unwrap().as_str())
+        .collect()
+}
+
+#[derive(Copy, Clone)]
+enum Reference {
+    Issue,
+    PullRequest,
+    Commit,
+    Profile,
+    Custom(usize),
+}
+
+fn builtin_rules(profiles_only: bool) -> Vec<(Regex, Reference)> {
+    let profile = (Regex::new(PROFILE).unwrap(), Reference::Profile);
+    if profiles_only {
+        return vec![profile];
+    }
+
+    // The issue rule is before the pull request rule, so `##12` is not matched as `#12`.
+    let qualified = |pattern: &str| Regex::new(&format!("{}{}", QUALIFIER, pattern)).unwrap();
+    vec![
+        (qualified(ISSUE), Reference::Issue),
+        (qualified(PULL_REQUEST), Reference::PullRequest),
+        (qualified(COMMIT), Reference::Commit),
+        profile,
+    ]
+}
+
+fn id_of<'t>(captures: &Captures<'t>) -> &'t str {
+    captures
+        .name("id")
+        .or_else(|| captures.get(0))
+        .map(|m| m.as_str())
+        .unwrap_or_default()
+}
+
+/// Replaces all references in a single pass, so the generated links are never matched again.
+/// With `profiles_only` set, only `@user` references are replaced, which is used for notices.
+pub fn replace_links<F: LinkFormatter + ?Sized>(
+    line: &str,
+    context: &LinkContext,
+    formatter: &F,
+    profiles_only: bool,
+) -> String {
+    let mut rules = builtin_rules(profiles_only);
+    if !profiles_only {
+        for (index, rule) in context.autolinks.iter().enumerate() {
+            rules.push((rule.regex.clone(), Reference::Custom(index)));
+        }
+    }
+
+    let mut line_to_print = String::new();
+    let mut position = 0;
+
+    loop {
+        // The earliest match wins, and for matches at the same position the first rule wins.
+        let next = rules
+            .iter()
+            .filter_map(|(regex, reference)| {
+                regex
+                    .captures_at(line, position)
+                    .filter(|captures| !captures[0].is_empty())
+                    .map(|captures| (captures, *reference))
+            })
+            .min_by_key(|(captures, _)| captures.get(0).unwrap().start());
+
+        let Some((captures, reference)) = next else {
+            break;
+        };
+
+        let whole = captures.get(0).unwrap();
+        let id = id_of(&captures);
+
+        // Only the builtin rules have qualifiers, a custom pattern may use the group name for something else.
+        // A word before the reference that is not a qualifier, and the character before a qualifier, are kept as text.
+        let (qualifier, start) = match (reference, captures.name("repo")) {
+            (Reference::Custom(_), _) | (_, None) => (None, whole.start()),
+            (_, Some(word)) => match qualifier(&captures, |key| context.repos.contains_key(key)) {
+                Some(qualifier) => (Some(qualifier), word.start()),
+                None => (None, word.end()),
+            },
+        };
+        let prefix = qualifier.unwrap_or_default();
+
+        // Unknown repos are reported by the validation, the text is kept as it is.
+        // So is a number too large to be an issue or pull request.
+        let number = id.parse::<usize>().ok();
+        let link = match reference {
+            Reference::Issue => number.zip(context.resolve(qualifier)).map(|(number, (repo, urls))| {
+                formatter.link(&format!("{}#{}", prefix, number), &urls.issue(repo, number))
+            }),
+            Reference::PullRequest => number.zip(context.resolve(qualifier)).map(|(number, (repo, urls))| {
+                formatter.link(&format!("{}#{}", prefix, number), &urls.pull_request(repo, number))
+            }),
+            // Qualified commits are shown as `clog@abc123`, like GitHub does.
+            Reference::Commit => context.resolve(qualifier).map(|(repo, urls)| {
+                let hash = if repo == context.commits.repo {
+                    context.commits.full_hashes.get(id).map_or(id, String::as_str)
+                } else {
+                    id
+                };
+                let shown_hash = match context.commit_abbreviation {
+                    Some(length) if hash.len() > length => &hash[..length],
+                    _ => hash,
+                };
+                let name = match qualifier {
+                    Some(qualifier) => format!("{}@{}", qualifier, shown_hash),
+                    None => shown_hash.to_string(),
+                };
+                formatter.link(&name, &urls.commit(repo, hash))
+            }),
+            Reference::Profile => Some(formatter.link(whole.as_str(), &context.urls.profile(id))),
+            Reference::Custom(index) => {
+                let url = expand(&context.autolinks[index].url, &[("id", id)]);
+                Some(formatter.link(whole.as_str(), &url))
+            }
+        };
+        let link = link.unwrap_or_else(|| line[start..whole.end()].to_string());
+
+        line_to_print += &line[position..start];
+        line_to_print += &link;
+        position = whole.end();
+    }
+
+    line_to_print += &line[position..];
+    line_to_print
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::DateFormat;
+    use crate::formatters::markdown_github::MarkdownGitHubFormatter;
+
+    const DOCUMENT: &str = r"
+repo: piot/nimble
+releases:
+  0.0.1:
+    date: 2024-01-01
+repos:
+  clog:
+    repo: piot/clog
+    name: Clog
+    description: Logging
+";
+
+    fn render(line: &str) -> String {
+        let document: Document = serde_yaml::from_str(DOCUMENT).unwrap();
+        let links = DocumentLinks::without_commits(&document);
+        let formatter = MarkdownGitHubFormatter {
+            date_format: DateFormat::default(),
+        };
+        replace_links(line, &links.document(), &formatter, false)
+    }
+
+    #[test]
+    fn word_before_reference_is_not_a_qualifier() {
+        assert_eq!(
+            render("crash on connect (PR#12)"),
+            "crash on connect (PR[#12](https://github.com/piot/nimble/pull/12))"
+        );
+        assert!(unknown_qualifiers("crash on connect (PR#12)", &["clog"]).is_empty());
+    }
+
+    #[test]
+    fn key_qualifier() {
+        assert_eq!(render("fix clog#12"), "fix [clog#12](https://github.com/piot/clog/pull/12)");
+        assert!(unknown_qualifiers("fix clog#12", &["clog"]).is_empty());
+        assert_eq!(unknown_qualifiers("fix clgo#12", &["clog"]), vec![("clgo", Some("clog"))]);
+        assert_eq!(unknown_qualifiers("fixed netcode#12 crash", &["clog"]), vec![("netcode", None)]);
+        assert!(unknown_qualifiers("see GH#12 and pr#13", &["clog"]).is_empty());
+    }
+
+    #[test]
+    fn slug_qualifier() {
+        assert_eq!(
+            render("fix (piot/clog$abc)"),
+            "fix ([piot/clog@abc](https://github.com/piot/clog/commit/abc))"
+        );
+        assert!(unknown_qualifiers("fix (piot/clog$abc)", &["clog"]).is_empty());
+    }
+
+    #[test]
+    fn number_too_large_is_not_linked() {
+        assert_eq!(
+            render("issue #123456789012345678901234 fixed"),
+            "issue #123456789012345678901234 fixed"
+        );
+        assert_eq!(render("see clog##123456789012345678901234"), "see clog##123456789012345678901234");
+    }
+
+    #[test]
+    fn pull_request_references_skip_numbers_that_do_not_fit() {
+        assert_eq!(
+            pull_request_references("fix clog#12, ##7 and #123456789012345678901234"),
+            vec![(Some("clog"), 12)]
+        );
+    }
+
+    #[test]
+    fn qualifier_needs_word_boundary() {
+        assert_eq!(
+            commit_references("price is now US$100", |key| key == "clog"),
+            vec![(None, "100")]
+        );
+        assert_eq!(commit_references("see clog$abc", |key| key == "clog"), vec![(Some("clog"), "abc")]);
+    }
+}
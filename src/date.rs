@@ -0,0 +1,90 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use std::fmt::Write;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{Locale, NaiveDate};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+
+pub const ISO_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Parses an ISO-8601 calendar date. Only the complete `YYYY-MM-DD` form is accepted.
+pub fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    let is_complete_form = s.len() == 10
+        && s.char_indices().all(|(index, c)| match index {
+            4 | 7 => c == '-',
+            _ => c.is_ascii_digit(),
+        });
+    if !is_complete_form {
+        return Err(format!("invalid date '{}', expected YYYY-MM-DD", s));
+    }
+
+    NaiveDate::parse_from_str(s, ISO_DATE_FORMAT).map_err(|err| format!("invalid date '{}': {}", s, err))
+}
+
+pub fn deserialize_optional_date<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<NaiveDate>, D::Error> {
+    let s: Option<String> = Option::deserialize(deserializer)?;
+    s.map(|s| parse_date(s.trim()).map_err(D::Error::custom))
+        .transpose()
+}
+
+/// How the formatters display a date, e.g. `%B %-d, %Y` in `en_US` for "June 22, 2023".
+#[derive(Debug, Clone)]
+pub struct DateFormat {
+    pub pattern: String,
+    pub locale: Locale,
+}
+
+impl Default for DateFormat {
+    fn default() -> Self {
+        Self {
+            pattern: ISO_DATE_FORMAT.to_string(),
+            locale: Locale::POSIX,
+        }
+    }
+}
+
+impl DateFormat {
+    /// Fails for an unknown locale, and for a pattern with an unknown specifier like `%Q`
+    /// or a field that a date does not have like `%H`.
+    pub fn new(pattern: Option<&str>, locale: Option<&str>) -> Result<DateFormat, String> {
+        let mut date_format = DateFormat::default();
+        if let Some(pattern) = pattern {
+            if StrftimeItems::new(pattern).any(|item| item == Item::Error) {
+                return Err(format!("invalid date format '{}'", pattern));
+            }
+            date_format.pattern = pattern.to_string();
+        }
+        if let Some(locale) = locale {
+            date_format.locale =
+                Locale::try_from(locale).map_err(|_| format!("unknown locale '{}'", locale))?;
+        }
+        // Formatting fails, instead of panicking, when the pattern uses a time or time zone field.
+        let mut sample = String::new();
+        if write!(sample, "{}", NaiveDate::MIN.format_localized(&date_format.pattern, date_format.locale)).is_err() {
+            return Err(format!("date format '{}' uses a field that a date does not have", date_format.pattern));
+        }
+        Ok(date_format)
+    }
+
+    pub fn format(&self, date: &NaiveDate) -> String {
+        date.format_localized(&self.pattern, self.locale).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_date_formats_are_rejected() {
+        assert!(DateFormat::new(Some("%Q"), None).is_err());
+        assert!(DateFormat::new(Some("%H:%M"), None).is_err());
+        assert!(DateFormat::new(Some("%B %-d, %Y"), Some("sv_SE")).is_ok());
+    }
+}
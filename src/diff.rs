@@ -0,0 +1,346 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::autolink::DocumentLinks;
+use crate::formatter::{EmojiFormatter, HeadingFormatter, LinkFormatter};
+use crate::version::is_unreleased;
+use crate::yaml::{Document, Release};
+use crate::{replace_line, CategoryType};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl ChangeKind {
+    fn description(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::Modified => "modified",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValueDiff {
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EntryDiff {
+    // Scope is `section`, `package` or `repo`.
+    pub scope: &'static str,
+    pub name: String,
+    pub category: CategoryType,
+    pub change: ChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReleaseDiff {
+    pub version: String,
+    pub change: ChangeKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<ValueDiff>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notice: Option<ValueDiff>,
+    pub entries: Vec<EntryDiff>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChangelogDiff {
+    pub releases: Vec<ReleaseDiff>,
+}
+
+type EntryGroups = IndexMap<(&'static str, String, CategoryType), Vec<String>>;
+
+fn entry_groups(release: Option<&Release>) -> EntryGroups {
+    let mut groups = EntryGroups::new();
+    for (scope, changes) in release.map(Release::scoped_changes).unwrap_or_default() {
+        for (category, entry) in changes.entries() {
+            groups
+                .entry((scope.kind(), scope.name().to_string(), category))
+                .or_default()
+                .push(entry.text.trim().to_string());
+        }
+    }
+    groups
+}
+
+fn value_diff<T: ToString + PartialEq>(old: Option<&T>, new: Option<&T>) -> Option<ValueDiff> {
+    (old != new).then(|| ValueDiff {
+        old: old.map(T::to_string),
+        new: new.map(T::to_string),
+    })
+}
+
+/// Entries only in the old release are removed and entries only in the new release are added.
+/// Within the same scope and category, removed and added entries are paired up in order as modified entries.
+fn diff_entries(old: Option<&Release>, new: Option<&Release>) -> Vec<EntryDiff> {
+    let old_groups = entry_groups(old);
+    let new_groups = entry_groups(new);
+    let mut keys: Vec<_> = new_groups.keys().collect();
+    keys.extend(old_groups.keys().filter(|key| !new_groups.contains_key(*key)));
+
+    let mut entries = Vec::new();
+    for key in keys {
+        let (scope, name, category) = key;
+        let old_entries = old_groups.get(key).cloned().unwrap_or_default();
+        let new_entries = new_groups.get(key).cloned().unwrap_or_default();
+        let removed: Vec<&String> = old_entries.iter().filter(|entry| !new_entries.contains(entry)).collect();
+        let added: Vec<&String> = new_entries.iter().filter(|entry| !old_entries.contains(entry)).collect();
+
+        let entry = |change, old: Option<&String>, new: Option<&String>| EntryDiff {
+            scope,
+            name: name.clone(),
+            category: *category,
+            change,
+            old: old.cloned(),
+            new: new.cloned(),
+        };
+
+        let paired = removed.len().min(added.len());
+        for index in 0..paired {
+            entries.push(entry(ChangeKind::Modified, Some(removed[index]), Some(added[index])));
+        }
+        for old_entry in &removed[paired..] {
+            entries.push(entry(ChangeKind::Removed, Some(old_entry), None));
+        }
+        for new_entry in &added[paired..] {
+            entries.push(entry(ChangeKind::Added, None, Some(new_entry)));
+        }
+    }
+    entries
+}
+
+/// Compares two documents release by release, in the order of the new document.
+pub fn diff_documents(old: &Document, new: &Document) -> ChangelogDiff {
+    let mut versions: Vec<&String> = new.releases.keys().collect();
+    versions.extend(old.releases.keys().filter(|version| !new.releases.contains_key(*version)));
+
+    let mut releases = Vec::new();
+    for version in versions {
+        let old_release = old.releases.get(version);
+        let new_release = new.releases.get(version);
+        let change = match (old_release, new_release) {
+            (None, _) => ChangeKind::Added,
+            (_, None) => ChangeKind::Removed,
+            _ => ChangeKind::Modified,
+        };
+
+        let release_diff = ReleaseDiff {
+            version: version.clone(),
+            change,
+            date: value_diff(
+                old_release.and_then(|release| release.date.as_ref()),
+                new_release.and_then(|release| release.date.as_ref()),
+            ),
+            notice: value_diff(
+                old_release.and_then(|release| release.notice.as_ref()),
+                new_release.and_then(|release| release.notice.as_ref()),
+            ),
+            entries: diff_entries(old_release, new_release),
+        };
+
+        let is_unchanged = change == ChangeKind::Modified
+            && release_diff.date.is_none()
+            && release_diff.notice.is_none()
+            && release_diff.entries.is_empty();
+        if !is_unchanged {
+            releases.push(release_diff);
+        }
+    }
+
+    ChangelogDiff { releases }
+}
+
+/// Prints the diff as a changelog, where every entry is labeled as added, removed or modified.
+pub fn print_diff<F: LinkFormatter + HeadingFormatter + EmojiFormatter + ?Sized>(
+    diff: &ChangelogDiff,
+    document: &Document,
+    formatter: &F,
+) {
+    let document_links = DocumentLinks::without_commits(document);
+
+    println!("{}", formatter.heading(1, "Changelog diff"));
+
+    for release in &diff.releases {
+        let version_name = match is_unreleased(&release.version) {
+            true => "Unreleased",
+            false => release.version.as_str(),
+        };
+        let heading = format!("{} {} [{}]", formatter.emoji_tag(), version_name, release.change.description());
+        println!("\n{}\n", formatter.heading(2, &heading));
+
+        for (name, value) in [("date", &release.date), ("notice", &release.notice)] {
+            if let Some(value) = value {
+                println!(
+                    "* {}: {} -> {}",
+                    name,
+                    value.old.as_deref().unwrap_or("none"),
+                    value.new.as_deref().unwrap_or("none")
+                );
+            }
+        }
+
+        let mut previous_scope = None;
+        for entry in &release.entries {
+            let scope = (entry.scope, entry.name.as_str());
+            if previous_scope != Some(scope) {
+                println!("\n{}\n", formatter.heading(3, &format!("{} {}", entry.scope, entry.name)));
+                previous_scope = Some(scope);
+            }
+
+            let links = match entry.scope {
+                "repo" => document_links.repo(&entry.name),
+                _ => None,
+            }
+            .unwrap_or_else(|| document_links.document());
+
+            let text = entry.new.as_ref().or(entry.old.as_ref()).expect("entry has old or new text");
+            let mut line = format!(
+                "* {}[{}] {}",
+                formatter.emoji(&entry.category),
+                entry.change.description(),
+                replace_line(text, &links, formatter)
+            );
+            if let (ChangeKind::Modified, Some(old)) = (entry.change, &entry.old) {
+                line += &format!(" (was: {})", replace_line(old, &links, formatter));
+            }
+            println!("{}", line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn document(yaml: &str) -> Document {
+        serde_yaml::from_str(&format!("repo: piot/nimble\nreleases:\n{}", yaml)).unwrap()
+    }
+
+    fn summary(diff: &ChangelogDiff) -> Vec<String> {
+        diff.releases
+            .iter()
+            .flat_map(|release| {
+                let entries = release.entries.iter().map(move |entry| {
+                    format!(
+                        "{} {} {}: {:?} -> {:?}",
+                        release.version,
+                        entry.change.description(),
+                        entry.name,
+                        entry.old,
+                        entry.new
+                    )
+                });
+                std::iter::once(format!("{} {}", release.version, release.change.description())).chain(entries)
+            })
+            .collect()
+    }
+
+    const OLD: &str = r"
+  1.1.0:
+    date: 2024-02-01
+    sections:
+      general:
+        changes:
+          fixed:
+            - Crash on connect
+            - Leak in lobby
+            - Slow startup
+          added:
+            - Chat
+  1.0.0:
+    date: 2024-01-01
+    sections:
+      general:
+        changes:
+          added:
+            - Lobby
+  0.9.0:
+    date: 2023-12-01
+";
+
+    #[test]
+    fn entries_are_paired_in_order() {
+        let new = r"
+  1.1.0:
+    date: 2024-02-01
+    sections:
+      general:
+        changes:
+          fixed:
+            - Crash on connect
+            - Leak in the lobby
+            - Faster startup
+            - Desync after reconnect
+  1.0.0:
+    date: 2024-01-01
+    sections:
+      general:
+        changes:
+          added:
+            - Lobby
+";
+        assert_eq!(
+            summary(&diff_documents(&document(OLD), &document(new))),
+            vec![
+                "1.1.0 modified",
+                "1.1.0 modified general: Some(\"Leak in lobby\") -> Some(\"Leak in the lobby\")",
+                "1.1.0 modified general: Some(\"Slow startup\") -> Some(\"Faster startup\")",
+                "1.1.0 added general: None -> Some(\"Desync after reconnect\")",
+                "1.1.0 removed general: Some(\"Chat\") -> None",
+                "0.9.0 removed",
+            ]
+        );
+    }
+
+    #[test]
+    fn added_release_and_unchanged_releases() {
+        let added = "  1.2.0:\n    sections:\n      general:\n        changes:\n          added:\n            - Replays\n";
+        let new = format!("{}{}", added, OLD);
+        assert_eq!(
+            summary(&diff_documents(&document(OLD), &document(&new))),
+            vec!["1.2.0 added", "1.2.0 added general: None -> Some(\"Replays\")"]
+        );
+        assert!(diff_documents(&document(OLD), &document(OLD)).releases.is_empty());
+    }
+
+    #[test]
+    fn json_output() {
+        let new = OLD.replace("2024-01-01", "2024-01-02").replace("- Lobby", "- Lobbies");
+        let diff = diff_documents(&document(OLD), &document(&new));
+        assert_eq!(
+            serde_json::to_value(&diff).unwrap(),
+            json!({
+                "releases": [{
+                    "version": "1.0.0",
+                    "change": "modified",
+                    "date": { "old": "2024-01-01", "new": "2024-01-02" },
+                    "entries": [{
+                        "scope": "section",
+                        "name": "general",
+                        "category": "added",
+                        "change": "modified",
+                        "old": "Lobby",
+                        "new": "Lobbies",
+                    }],
+                }]
+            })
+        );
+    }
+}
@@ -0,0 +1,235 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use serde::Deserialize;
+
+use crate::links::UrlTemplates;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeType {
+    #[default]
+    GitHub,
+    GitLab,
+    Gitea,
+    Forgejo,
+    Bitbucket,
+    SourceHut,
+}
+
+impl ForgeType {
+    fn default_url(&self) -> &'static str {
+        match self {
+            ForgeType::GitHub => "https://github.com",
+            ForgeType::GitLab => "https://gitlab.com",
+            ForgeType::Gitea => "https://gitea.com",
+            ForgeType::Forgejo => "https://codeberg.org",
+            ForgeType::Bitbucket => "https://bitbucket.org",
+            ForgeType::SourceHut => "https://git.sr.ht",
+        }
+    }
+}
+
+/// Where a repository is hosted, e.g. `type: gitlab` with `url: https://gitlab.example.com`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct Forge {
+    #[serde(rename = "type")]
+    pub forge_type: ForgeType,
+
+    // Url is the base url of a self-hosted instance. Defaults to the public instance of the forge.
+    pub url: Option<String>,
+
+    // List is the sourcehut mailing list that receives the patches, e.g. `~sircmpwn/public-inbox`.
+    // Defaults to a list with the same name as the repository.
+    pub list: Option<String>,
+}
+
+// sourcehut hosts the profiles, issues and patches as separate services next to git,
+// e.g. `sr.ht` and `lists.sr.ht` for `git.sr.ht`.
+fn sourcehut_service(base: &str, service: Option<&str>) -> String {
+    match (base.split_once("://git."), service) {
+        (Some((scheme, host)), Some(service)) => format!("{}://{}.{}", scheme, service, host),
+        (Some((scheme, host)), None) => format!("{}://{}", scheme, host),
+        (None, _) => base.to_string(),
+    }
+}
+
+impl Forge {
+    pub fn base_url(&self) -> String {
+        self.url
+            .as_deref()
+            .unwrap_or(self.forge_type.default_url())
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    /// Default url templates following the url layout of the forge.
+    pub fn url_templates(&self) -> UrlTemplates {
+        let base = self.base_url();
+        let repo = format!("{}/{{repo}}", base);
+
+        match self.forge_type {
+            ForgeType::GitHub => UrlTemplates {
+                release: format!("{}/releases/tag/{{tag}}", repo),
+                download: format!("{}/releases/download/{{tag}}/{{name}}", repo),
+                compare: format!("{}/compare/{{from}}...{{to}}", repo),
+                pull_request: format!("{}/pull/{{number}}", repo),
+                issue: format!("{}/issues/{{number}}", repo),
+                commit: format!("{}/commit/{{hash}}", repo),
+                profile: format!("{}/{{user}}", base),
+                repository: repo,
+            },
+            ForgeType::GitLab => UrlTemplates {
+                release: format!("{}/-/tags/{{tag}}", repo),
+                download: format!("{}/-/releases/{{tag}}/downloads/{{name}}", repo),
+                compare: format!("{}/-/compare/{{from}}...{{to}}", repo),
+                pull_request: format!("{}/-/merge_requests/{{number}}", repo),
+                issue: format!("{}/-/issues/{{number}}", repo),
+                commit: format!("{}/-/commit/{{hash}}", repo),
+                profile: format!("{}/{{user}}", base),
+                repository: repo,
+            },
+            ForgeType::Gitea | ForgeType::Forgejo => UrlTemplates {
+                release: format!("{}/releases/tag/{{tag}}", repo),
+                download: format!("{}/releases/download/{{tag}}/{{name}}", repo),
+                compare: format!("{}/compare/{{from}}...{{to}}", repo),
+                pull_request: format!("{}/pulls/{{number}}", repo),
+                issue: format!("{}/issues/{{number}}", repo),
+                commit: format!("{}/commit/{{hash}}", repo),
+                profile: format!("{}/{{user}}", base),
+                repository: repo,
+            },
+            ForgeType::Bitbucket => UrlTemplates {
+                release: format!("{}/src/{{tag}}", repo),
+                download: format!("{}/downloads/{{name}}", repo),
+                compare: format!("{}/branches/compare/{{to}}%0D{{from}}", repo),
+                pull_request: format!("{}/pull-requests/{{number}}", repo),
+                issue: format!("{}/issues/{{number}}", repo),
+                commit: format!("{}/commits/{{hash}}", repo),
+                profile: format!("{}/{{user}}/", base),
+                repository: repo,
+            },
+            // sourcehut has no compare view, so the log from the newer ref is the closest match.
+            // The repository is written with the owner's tilde, e.g. `~sircmpwn/scdoc`.
+            ForgeType::SourceHut => UrlTemplates {
+                release: format!("{}/refs/{{tag}}", repo),
+                download: format!("{}/refs/download/{{tag}}/{{name}}", repo),
+                compare: format!("{}/log/{{to}}", repo),
+                pull_request: format!(
+                    "{}/{}/patches/{{number}}",
+                    sourcehut_service(&base, Some("lists")),
+                    self.list.as_deref().unwrap_or("{repo}")
+                ),
+                issue: format!("{}/{{repo}}/{{number}}", sourcehut_service(&base, Some("todo"))),
+                commit: format!("{}/commit/{{hash}}", repo),
+                profile: format!("{}/~{{user}}", sourcehut_service(&base, None)),
+                repository: repo,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(forge: &Forge) -> Vec<String> {
+        let urls = forge.url_templates();
+        vec![
+            urls.repository("piot/nimble"),
+            urls.release("piot/nimble", "v1.0.0"),
+            urls.download("piot/nimble", "v1.0.0", "nimble.zip"),
+            urls.compare("piot/nimble", "v0.9.0", "v1.0.0"),
+            urls.pull_request("piot/nimble", 12),
+            urls.issue("piot/nimble", 3),
+            urls.commit("piot/nimble", "abc123"),
+            urls.profile("piot"),
+        ]
+    }
+
+    fn forge(forge_type: ForgeType, url: Option<&str>) -> Forge {
+        Forge {
+            forge_type,
+            url: url.map(str::to_string),
+            list: None,
+        }
+    }
+
+    #[test]
+    fn gitlab() {
+        assert_eq!(
+            links(&forge(ForgeType::GitLab, Some("https://gitlab.example.com/"))),
+            vec![
+                "https://gitlab.example.com/piot/nimble",
+                "https://gitlab.example.com/piot/nimble/-/tags/v1.0.0",
+                "https://gitlab.example.com/piot/nimble/-/releases/v1.0.0/downloads/nimble.zip",
+                "https://gitlab.example.com/piot/nimble/-/compare/v0.9.0...v1.0.0",
+                "https://gitlab.example.com/piot/nimble/-/merge_requests/12",
+                "https://gitlab.example.com/piot/nimble/-/issues/3",
+                "https://gitlab.example.com/piot/nimble/-/commit/abc123",
+                "https://gitlab.example.com/piot",
+            ]
+        );
+    }
+
+    #[test]
+    fn gitea_and_forgejo() {
+        let expected = |base: &str| -> Vec<String> {
+            vec![
+                format!("{}/piot/nimble", base),
+                format!("{}/piot/nimble/releases/tag/v1.0.0", base),
+                format!("{}/piot/nimble/releases/download/v1.0.0/nimble.zip", base),
+                format!("{}/piot/nimble/compare/v0.9.0...v1.0.0", base),
+                format!("{}/piot/nimble/pulls/12", base),
+                format!("{}/piot/nimble/issues/3", base),
+                format!("{}/piot/nimble/commit/abc123", base),
+                format!("{}/piot", base),
+            ]
+        };
+        assert_eq!(links(&forge(ForgeType::Gitea, None)), expected("https://gitea.com"));
+        assert_eq!(links(&forge(ForgeType::Forgejo, None)), expected("https://codeberg.org"));
+    }
+
+    #[test]
+    fn bitbucket() {
+        assert_eq!(
+            links(&forge(ForgeType::Bitbucket, None)),
+            vec![
+                "https://bitbucket.org/piot/nimble",
+                "https://bitbucket.org/piot/nimble/src/v1.0.0",
+                "https://bitbucket.org/piot/nimble/downloads/nimble.zip",
+                "https://bitbucket.org/piot/nimble/branches/compare/v1.0.0%0Dv0.9.0",
+                "https://bitbucket.org/piot/nimble/pull-requests/12",
+                "https://bitbucket.org/piot/nimble/issues/3",
+                "https://bitbucket.org/piot/nimble/commits/abc123",
+                "https://bitbucket.org/piot/",
+            ]
+        );
+    }
+
+    #[test]
+    fn sourcehut() {
+        let urls = forge(ForgeType::SourceHut, None).url_templates();
+        assert_eq!(urls.repository("~piot/nimble"), "https://git.sr.ht/~piot/nimble");
+        assert_eq!(urls.release("~piot/nimble", "v1.0.0"), "https://git.sr.ht/~piot/nimble/refs/v1.0.0");
+        assert_eq!(
+            urls.download("~piot/nimble", "v1.0.0", "nimble.zip"),
+            "https://git.sr.ht/~piot/nimble/refs/download/v1.0.0/nimble.zip"
+        );
+        assert_eq!(urls.compare("~piot/nimble", "v0.9.0", "v1.0.0"), "https://git.sr.ht/~piot/nimble/log/v1.0.0");
+        assert_eq!(urls.pull_request("~piot/nimble", 12), "https://lists.sr.ht/~piot/nimble/patches/12");
+        assert_eq!(urls.issue("~piot/nimble", 3), "https://todo.sr.ht/~piot/nimble/3");
+        assert_eq!(urls.commit("~piot/nimble", "abc123"), "https://git.sr.ht/~piot/nimble/commit/abc123");
+        assert_eq!(urls.profile("piot"), "https://sr.ht/~piot");
+
+        let with_list = Forge {
+            list: Some("~piot/nimble-devel".to_string()),
+            ..forge(ForgeType::SourceHut, Some("https://git.example.org"))
+        };
+        assert_eq!(
+            with_list.url_templates().pull_request("~piot/nimble", 12),
+            "https://lists.example.org/~piot/nimble-devel/patches/12"
+        );
+    }
+}
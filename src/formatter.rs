@@ -2,6 +2,7 @@
  *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
  *  Licensed under the MIT License. See LICENSE in the project root for license information.
  *--------------------------------------------------------------------------------------------------------*/
+use chrono::NaiveDate;
 use crate::CategoryType;
 
 pub(crate) enum AdmonitionType {
@@ -12,12 +13,21 @@ pub(crate) enum AdmonitionType {
 
 pub(crate) trait AdmonitionFormatter {
     fn admonition(&self, ad_type: AdmonitionType, content: &str) -> String;
+    /// Highlighted block with a title and a bullet list, e.g. the breaking changes of a release.
+    fn admonition_list(&self, ad_type: AdmonitionType, title: &str, items: &[String]) -> String;
 }
 
 pub(crate) trait LinkFormatter {
     fn link(&self, name: &str, link: &str) -> String;
 }
 
+pub(crate) trait LinkDefinitionFormatter {
+    /// Link reference definition placed at the end of the document, if the format supports them.
+    fn link_definition(&self, name: &str, link: &str) -> Option<String>;
+    /// Link to the reference definition with the name.
+    fn reference_link(&self, name: &str) -> String;
+}
+
 pub(crate) trait EmojiFormatter {
     fn emoji(&self, category_type: &CategoryType) -> String;
     fn emoji_tag(&self) -> String;
@@ -27,5 +37,22 @@ pub(crate) trait HeadingFormatter {
     fn heading(&self, level: usize, name: &str) -> String;
 }
 
-#[allow(unused)]
-pub(crate) trait SuperFormatter: AdmonitionFormatter + LinkFormatter + HeadingFormatter + EmojiFormatter {}
+pub(crate) trait StrikethroughFormatter {
+    fn strikethrough(&self, text: &str) -> String;
+}
+
+pub(crate) trait BadgeFormatter {
+    /// Small status image, e.g. the severity of an advisory, optionally linked.
+    fn badge(&self, alt: &str, image: &str, link: Option<&str>) -> String;
+}
+
+pub(crate) trait TableFormatter {
+    /// Table with a header row. Cells may contain links.
+    fn table(&self, headers: &[&str], rows: &[Vec<String>]) -> String;
+}
+
+pub(crate) trait DateFormatter {
+    fn date(&self, date: &NaiveDate) -> String;
+}
+
+pub(crate) trait SuperFormatter: AdmonitionFormatter + LinkFormatter + LinkDefinitionFormatter + HeadingFormatter + EmojiFormatter + DateFormatter + StrikethroughFormatter + BadgeFormatter + TableFormatter {}
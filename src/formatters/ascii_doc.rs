@@ -2,11 +2,15 @@
  *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
  *  Licensed under the MIT License. See LICENSE in the project root for license information.
  *--------------------------------------------------------------------------------------------------------*/
+use chrono::NaiveDate;
 use crate::CategoryType;
+use crate::date::DateFormat;
 use crate::emoji::utf8_icon;
-use crate::formatter::{AdmonitionFormatter, AdmonitionType, EmojiFormatter, HeadingFormatter, LinkFormatter, SuperFormatter};
+use crate::formatter::{AdmonitionFormatter, AdmonitionType, BadgeFormatter, DateFormatter, EmojiFormatter, HeadingFormatter, LinkDefinitionFormatter, LinkFormatter, StrikethroughFormatter, SuperFormatter, TableFormatter};
 
-pub(crate) struct AsciiDocFormatter {}
+pub(crate) struct AsciiDocFormatter {
+    pub date_format: DateFormat,
+}
 
 impl SuperFormatter for AsciiDocFormatter {}
 
@@ -26,6 +30,17 @@ impl AdmonitionFormatter for AsciiDocFormatter {
             text
         )
     }
+
+    fn admonition_list(&self, admonition_type: AdmonitionType, title: &str, items: &[String]) -> String {
+        let mut lines = vec![
+            format!("[{}]", admonition_type_to_asciidoc_keyword(admonition_type)),
+            format!(".{}", title),
+            "====".to_string(),
+        ];
+        lines.extend(items.iter().map(|item| format!("* {}", item)));
+        lines.push("====".to_string());
+        lines.join("\n")
+    }
 }
 
 impl HeadingFormatter for AsciiDocFormatter {
@@ -44,6 +59,17 @@ impl LinkFormatter for AsciiDocFormatter {
     }
 }
 
+impl LinkDefinitionFormatter for AsciiDocFormatter {
+    fn link_definition(&self, _name: &str, _link: &str) -> Option<String> {
+        // AsciiDoc has no link reference definitions, the links are always inline.
+        None
+    }
+
+    fn reference_link(&self, name: &str) -> String {
+        // Never used, since there are no definitions to refer to.
+        name.to_string()
+    }
+}
 
 impl EmojiFormatter for AsciiDocFormatter {
     fn emoji(&self, category_type: &CategoryType) -> String {
@@ -55,3 +81,38 @@ impl EmojiFormatter for AsciiDocFormatter {
         "bookmark".to_string()
     }
 }
+
+impl StrikethroughFormatter for AsciiDocFormatter {
+    fn strikethrough(&self, text: &str) -> String {
+        format!("[.line-through]#{}#", text)
+    }
+}
+
+impl BadgeFormatter for AsciiDocFormatter {
+    fn badge(&self, alt: &str, image: &str, link: Option<&str>) -> String {
+        match link {
+            Some(link) => format!("image:{}[\"{}\",link=\"{}\"]", image, alt, link),
+            None => format!("image:{}[\"{}\"]", image, alt),
+        }
+    }
+}
+
+impl TableFormatter for AsciiDocFormatter {
+    fn table(&self, headers: &[&str], rows: &[Vec<String>]) -> String {
+        let row = |cells: Vec<String>| cells.iter().map(|cell| format!("|{}", cell)).collect::<Vec<_>>().join(" ");
+        let mut lines = vec![
+            "[options=\"header\"]".to_string(),
+            "|===".to_string(),
+            row(headers.iter().map(|header| header.to_string()).collect()),
+        ];
+        lines.extend(rows.iter().map(|cells| row(cells.iter().map(|cell| cell.replace('|', "\\|")).collect())));
+        lines.push("|===".to_string());
+        lines.join("\n")
+    }
+}
+
+impl DateFormatter for AsciiDocFormatter {
+    fn date(&self, date: &NaiveDate) -> String {
+        self.date_format.format(date)
+    }
+}
@@ -2,11 +2,15 @@
  *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
  *  Licensed under the MIT License. See LICENSE in the project root for license information.
  *--------------------------------------------------------------------------------------------------------*/
+use chrono::NaiveDate;
 use crate::CategoryType;
+use crate::date::DateFormat;
 use crate::emoji::utf8_icon;
-use crate::formatter::{AdmonitionFormatter, AdmonitionType, EmojiFormatter, HeadingFormatter, LinkFormatter, SuperFormatter};
+use crate::formatter::{AdmonitionFormatter, AdmonitionType, BadgeFormatter, DateFormatter, EmojiFormatter, HeadingFormatter, LinkDefinitionFormatter, LinkFormatter, StrikethroughFormatter, SuperFormatter, TableFormatter};
 
-pub(crate) struct MarkdownGitHubFormatter {}
+pub(crate) struct MarkdownGitHubFormatter {
+    pub date_format: DateFormat,
+}
 
 impl SuperFormatter for MarkdownGitHubFormatter {}
 
@@ -26,6 +30,15 @@ impl AdmonitionFormatter for MarkdownGitHubFormatter {
             text
         )
     }
+
+    fn admonition_list(&self, admonition_type: AdmonitionType, title: &str, items: &[String]) -> String {
+        let mut lines = vec![
+            format!("> [!{}]", admonition_type_to_github_name(admonition_type)),
+            format!("> **{}**", title),
+        ];
+        lines.extend(items.iter().map(|item| format!("> * {}", item)));
+        lines.join("\n")
+    }
 }
 
 impl HeadingFormatter for MarkdownGitHubFormatter {
@@ -45,6 +58,16 @@ impl LinkFormatter for MarkdownGitHubFormatter {
     }
 }
 
+impl LinkDefinitionFormatter for MarkdownGitHubFormatter {
+    fn link_definition(&self, name: &str, link: &str) -> Option<String> {
+        Some(format!("[{}]: {}", name, link))
+    }
+
+    fn reference_link(&self, name: &str) -> String {
+        format!("[{}]", name)
+    }
+}
+
 impl EmojiFormatter for MarkdownGitHubFormatter {
     fn emoji(&self, category_type: &CategoryType) -> String {
         utf8_icon(category_type).to_string()
@@ -54,3 +77,37 @@ impl EmojiFormatter for MarkdownGitHubFormatter {
         "🔖".to_string()
     }
 }
+
+impl StrikethroughFormatter for MarkdownGitHubFormatter {
+    fn strikethrough(&self, text: &str) -> String {
+        format!("~~{}~~", text)
+    }
+}
+
+impl BadgeFormatter for MarkdownGitHubFormatter {
+    fn badge(&self, alt: &str, image: &str, link: Option<&str>) -> String {
+        let image = format!("![{}]({})", alt, image);
+        match link {
+            Some(link) => format!("[{}]({})", image, link),
+            None => image,
+        }
+    }
+}
+
+impl TableFormatter for MarkdownGitHubFormatter {
+    fn table(&self, headers: &[&str], rows: &[Vec<String>]) -> String {
+        let row = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+        let mut lines = vec![
+            row(headers.iter().map(|header| header.to_string()).collect()),
+            row(headers.iter().map(|_| "---".to_string()).collect()),
+        ];
+        lines.extend(rows.iter().map(|cells| row(cells.iter().map(|cell| cell.replace('|', "\\|")).collect())));
+        lines.join("\n")
+    }
+}
+
+impl DateFormatter for MarkdownGitHubFormatter {
+    fn date(&self, date: &NaiveDate) -> String {
+        self.date_format.format(date)
+    }
+}
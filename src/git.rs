@@ -0,0 +1,165 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use chrono::NaiveDate;
+
+use crate::autolink::commit_references;
+use crate::date::parse_date;
+use crate::validate::ValidationError;
+use crate::yaml::{Document, Scope};
+
+/// A local clone of a repository, used through the `git` command line tool.
+pub struct GitRepository {
+    pub path: PathBuf,
+}
+
+pub struct Tag {
+    pub name: String,
+    // Date is the tagger date for annotated tags, and the commit date for lightweight tags.
+    pub date: NaiveDate,
+}
+
+pub enum CommitLookup {
+    Found(String),
+    Unknown,
+    Ambiguous,
+}
+
+impl GitRepository {
+    pub fn open(path: &Path) -> io::Result<GitRepository> {
+        let repository = GitRepository {
+            path: path.to_path_buf(),
+        };
+        let output = repository.git(&["rev-parse", "--git-dir"])?;
+        if !output.status.success() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("'{}' is not a git repository", path.display()),
+            ));
+        }
+        Ok(repository)
+    }
+
+    fn git(&self, args: &[&str]) -> io::Result<std::process::Output> {
+        Command::new("git").arg("-C").arg(&self.path).args(args).output()
+    }
+
+    pub fn tags(&self) -> io::Result<Vec<Tag>> {
+        let output = self.git(&["for-each-ref", "--format=%(refname:strip=2)%09%(creatordate:short)", "refs/tags"])?;
+        if !output.status.success() {
+            return Err(io::Error::other(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+
+        let mut tags = Vec::new();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let Some((name, date)) = line.split_once('\t') else {
+                continue;
+            };
+            let date = parse_date(date).map_err(io::Error::other)?;
+            tags.push(Tag {
+                name: name.to_string(),
+                date,
+            });
+        }
+        Ok(tags)
+    }
+
+    /// The contents of a file at a ref, e.g. `origin/main`. The path is relative to the root of the repository.
+    pub fn show_file(&self, git_ref: &str, path_in_repository: &str) -> io::Result<String> {
+        let output = self.git(&["show", &format!("{}:{}", git_ref, path_in_repository)])?;
+        if !output.status.success() {
+            return Err(io::Error::other(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// The path of a file in the working tree, relative to the root of the repository.
+    pub fn path_in_repository(&self, file: &Path) -> io::Result<String> {
+        let output = self.git(&["rev-parse", "--show-toplevel"])?;
+        let root = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()).canonicalize()?;
+        let file = file.canonicalize()?;
+        let relative = file.strip_prefix(&root).map_err(|_| {
+            io::Error::other(format!("'{}' is not in the repository '{}'", file.display(), root.display()))
+        })?;
+        // git always uses forward slashes in `<ref>:<path>`.
+        let parts: Vec<String> = relative.iter().map(|part| part.to_string_lossy().to_string()).collect();
+        Ok(parts.join("/"))
+    }
+
+    /// Expands a (short) commit hash to the full hash.
+    pub fn lookup_commit(&self, hash: &str) -> io::Result<CommitLookup> {
+        let output = self.git(&["rev-parse", "--verify", "--end-of-options", &format!("{}^{{commit}}", hash)])?;
+        if output.status.success() {
+            let full_hash = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            return Ok(CommitLookup::Found(full_hash));
+        }
+
+        let error = String::from_utf8_lossy(&output.stderr);
+        if error.contains("ambiguous") {
+            Ok(CommitLookup::Ambiguous)
+        } else {
+            Ok(CommitLookup::Unknown)
+        }
+    }
+}
+
+/// The commits referenced in the document that were found in the local clone of the document repository.
+#[derive(Default, Clone)]
+pub struct VerifiedCommits {
+    pub repo: String,
+    // Full hashes by the hash written in the document.
+    pub full_hashes: HashMap<String, String>,
+}
+
+/// Looks up every commit reference to the document repository in the local clone.
+/// Commits in other repositories can not be verified and are skipped.
+pub fn verify_commits(
+    document: &Document,
+    repository: &GitRepository,
+) -> io::Result<(VerifiedCommits, Vec<ValidationError>)> {
+    let mut verified = VerifiedCommits {
+        repo: document.repo.clone(),
+        full_hashes: HashMap::new(),
+    };
+    let mut errors = Vec::new();
+
+    for (name, release) in &document.releases {
+        for (scope, changes) in release.scoped_changes() {
+            for (_, entry) in changes.entries() {
+                let is_known_key = |key: &str| document.repos.as_ref().is_some_and(|repos| repos.contains_key(key));
+                for (qualifier, hash) in commit_references(&entry.text, is_known_key) {
+                    let is_document_repo = match qualifier {
+                        None => !matches!(scope, Scope::Repo(_)),
+                        Some(qualifier) => qualifier == document.repo,
+                    };
+                    if !is_document_repo || verified.full_hashes.contains_key(hash) {
+                        continue;
+                    }
+
+                    let location = format!("{} ({})", name, scope);
+                    match repository.lookup_commit(hash)? {
+                        CommitLookup::Found(full_hash) => {
+                            verified.full_hashes.insert(hash.to_string(), full_hash);
+                        }
+                        CommitLookup::Unknown => errors.push(ValidationError {
+                            location,
+                            message: format!("commit '{}' is not found in {}", hash, repository.path.display()),
+                        }),
+                        CommitLookup::Ambiguous => errors.push(ValidationError {
+                            location,
+                            message: format!("commit '{}' is ambiguous, use a longer hash", hash),
+                        }),
+                    }
+                }
+            }
+        }
+    }
+
+    Ok((verified, errors))
+}
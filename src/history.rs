@@ -0,0 +1,233 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use indexmap::IndexMap;
+
+use crate::validate::ValidationError;
+use crate::version::is_unreleased;
+use crate::yaml::{Changes, Document, Entry, Section};
+use crate::CategoryType;
+
+type ScopedChanges = Option<IndexMap<String, Changes>>;
+type Sections = Option<IndexMap<String, Section>>;
+
+// Yanking a package release and adding the advisory of a security entry afterwards are not changes to the history.
+fn published_entries(changes: &Changes) -> Vec<(CategoryType, Entry)> {
+    changes
+        .entries()
+        .map(|(category, entry)| {
+            let entry = Entry {
+                advisory: None,
+                ..entry.clone()
+            };
+            (category, entry)
+        })
+        .collect()
+}
+
+fn same_changes(current: &ScopedChanges, base: &ScopedChanges) -> bool {
+    let current: Vec<_> = current.iter().flatten().map(|(name, changes)| (name, published_entries(changes))).collect();
+    let base: Vec<_> = base.iter().flatten().map(|(name, changes)| (name, published_entries(changes))).collect();
+    current == base
+}
+
+fn same_sections(current: &Sections, base: &Sections) -> bool {
+    let view = |sections: &Sections| -> Vec<_> {
+        sections
+            .iter()
+            .flatten()
+            .map(|(name, section)| (name.clone(), section.notice.clone(), published_entries(&section.changes)))
+            .collect()
+    };
+    view(current) == view(base)
+}
+
+pub struct HistoryReport {
+    // Errors are the changed published releases, unless changing the history is allowed.
+    pub errors: Vec<ValidationError>,
+    // Warnings are the changed published releases when changing the history is allowed.
+    pub warnings: Vec<ValidationError>,
+}
+
+/// Compares the published releases of the base and the current document.
+/// With `allow_changes`, e.g. to fix a typo in an old release, the changes are warnings instead of errors.
+pub fn compare_history(base: &Document, current: &Document, allow_changes: bool) -> HistoryReport {
+    let changes = changed_published_releases(base, current);
+    match allow_changes {
+        true => HistoryReport {
+            errors: Vec::new(),
+            warnings: changes,
+        },
+        false => HistoryReport {
+            errors: changes,
+            warnings: Vec::new(),
+        },
+    }
+}
+
+/// Reports every published release in the base document that was removed or changed in the current document.
+/// A release is published when it has a date. The unreleased block, and planned versions without a date,
+/// can be changed freely, and so can the `yanked` flags and the advisories.
+pub fn changed_published_releases(base: &Document, current: &Document) -> Vec<ValidationError> {
+    let mut changes = Vec::new();
+
+    for (name, base_release) in &base.releases {
+        if is_unreleased(name) || base_release.date.is_none() {
+            continue;
+        }
+
+        let Some(release) = current.releases.get(name) else {
+            changes.push(ValidationError {
+                location: name.clone(),
+                message: "published release was removed".to_string(),
+            });
+            continue;
+        };
+
+        let mut changed_parts = Vec::new();
+        if release.date != base_release.date {
+            changed_parts.push("date");
+        }
+        if release.notice != base_release.notice {
+            changed_parts.push("notice");
+        }
+        if !same_sections(&release.sections, &base_release.sections) {
+            changed_parts.push("sections");
+        }
+        if !same_changes(&release.packages, &base_release.packages) {
+            changed_parts.push("packages");
+        }
+        if !same_changes(&release.repos, &base_release.repos) {
+            changed_parts.push("repos");
+        }
+
+        if !changed_parts.is_empty() {
+            changes.push(ValidationError {
+                location: name.clone(),
+                message: format!("published release changed: {}", changed_parts.join(", ")),
+            });
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r"
+repo: piot/nimble
+releases:
+  unreleased:
+    sections:
+      general:
+        changes:
+          added:
+            - Replay viewer
+  1.1.0:
+    sections:
+      general:
+        changes:
+          added:
+            - Spectator mode
+  1.0.0:
+    date: 2024-01-01
+    notice: First stable release
+    sections:
+      general:
+        changes:
+          fixed:
+            - Crash on connect
+    packages:
+      nimble-auth:
+        security:
+          - Timing leak in token compare
+  0.9.0:
+    date: 2023-12-01
+    sections:
+      general:
+        changes:
+          added:
+            - Lobby
+";
+
+    fn changed(current: &str) -> Vec<String> {
+        let base: Document = serde_yaml::from_str(BASE).unwrap();
+        let current: Document = serde_yaml::from_str(current).unwrap();
+        changed_published_releases(&base, &current)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn unchanged_history() {
+        assert!(changed(BASE).is_empty());
+    }
+
+    #[test]
+    fn changed_date_notice_and_entry() {
+        assert_eq!(
+            changed(&BASE.replace("2024-01-01", "2024-01-02")),
+            vec!["1.0.0: published release changed: date"]
+        );
+        assert_eq!(
+            changed(&BASE.replace("First stable", "Stable")),
+            vec!["1.0.0: published release changed: notice"]
+        );
+        assert_eq!(
+            changed(&BASE.replace("Crash on connect", "Crash when connecting")),
+            vec!["1.0.0: published release changed: sections"]
+        );
+        assert_eq!(
+            changed(&BASE.replace("Timing leak", "Timing attack")),
+            vec!["1.0.0: published release changed: packages"]
+        );
+    }
+
+    #[test]
+    fn removed_release() {
+        let current = BASE.replace("  0.9.0:\n    date: 2023-12-01\n", "  0.9.0:\n");
+        assert_eq!(changed(&current), vec!["0.9.0: published release changed: date"]);
+
+        let end = BASE.find("  0.9.0:").unwrap();
+        assert_eq!(changed(&BASE[..end]), vec!["0.9.0: published release was removed"]);
+    }
+
+    #[test]
+    fn allowed_changes() {
+        // The unreleased block and the planned release without a date can change.
+        let current = BASE.replace("Replay viewer", "Replays").replace("Spectator mode", "Spectators");
+        assert!(changed(&current).is_empty());
+
+        // So can yanking a release or a package release, and adding an advisory.
+        let current = BASE
+            .replace("    notice: First", "    yanked: broken saves\n    notice: First")
+            .replace(
+                "          - Timing leak in token compare",
+                concat!(
+                    "          - text: Timing leak in token compare\n",
+                    "            advisory:\n",
+                    "              ids: [RUSTSEC-2024-0001]\n",
+                    "        yanked: true",
+                ),
+            );
+        assert!(changed(&current).is_empty());
+    }
+
+    #[test]
+    fn allowed_history_changes_are_warnings() {
+        let base: Document = serde_yaml::from_str(BASE).unwrap();
+        let current: Document = serde_yaml::from_str(&BASE.replace("2024-01-01", "2024-01-02")).unwrap();
+
+        let report = compare_history(&base, &current, false);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.warnings.is_empty());
+
+        let report = compare_history(&base, &current, true);
+        assert!(report.errors.is_empty());
+        assert_eq!(report.warnings.len(), 1);
+    }
+}
@@ -0,0 +1,457 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use std::collections::HashMap;
+
+use crate::version::is_unreleased;
+use crate::yaml::{Document, Entry, Scope};
+use crate::CategoryType;
+
+/// Where an entry is listed in the document.
+#[derive(Debug, Clone, Copy)]
+pub struct Occurrence<'a> {
+    pub version: &'a str,
+    pub scope: Scope<'a>,
+    pub category: CategoryType,
+    pub entry: &'a Entry,
+}
+
+/// An entry that starts something, e.g. a deprecation, and the later entry that ends it, if any.
+pub struct Lifecycle<'a> {
+    pub key: &'a str,
+    pub start: Occurrence<'a>,
+    pub end: Option<Occurrence<'a>>,
+    // Releases since is the number of released versions after the start, up to the end or the latest release.
+    // It is not set when the start is not released yet.
+    pub releases_since: Option<usize>,
+}
+
+pub struct Tracking<'a> {
+    pub lifecycles: Vec<Lifecycle<'a>>,
+    // Unmatched ends are the ends without an earlier start with the same key.
+    pub unmatched_ends: Vec<(&'a str, Occurrence<'a>)>,
+}
+
+impl<'a> Tracking<'a> {
+    fn open(&mut self, key: &str) -> Option<&mut Lifecycle<'a>> {
+        self.lifecycles
+            .iter_mut()
+            .find(|lifecycle| lifecycle.key == key && lifecycle.end.is_none())
+    }
+
+    pub fn unresolved(&self) -> impl Iterator<Item = &Lifecycle<'_>> {
+        self.lifecycles.iter().filter(|lifecycle| lifecycle.end.is_none())
+    }
+}
+
+/// Follows entries through the releases, oldest release first. `start_key` returns the key of an entry that starts
+/// a lifecycle, and `end_key` the key of an entry that ends the open lifecycle with the same key.
+pub fn track<'a, S, E>(document: &'a Document, start_key: S, end_key: E) -> Tracking<'a>
+where
+    S: Fn(CategoryType, &'a Entry) -> Option<&'a str>,
+    E: Fn(CategoryType, &'a Entry) -> Option<&'a str>,
+{
+    let mut tracking = Tracking {
+        lifecycles: Vec::new(),
+        unmatched_ends: Vec::new(),
+    };
+    let mut released_count = 0;
+    let mut released_before: HashMap<&str, usize> = HashMap::new();
+
+    for (version, release) in document.releases.iter().rev() {
+        released_before.insert(version, released_count);
+        if !is_unreleased(version) {
+            released_count += 1;
+        }
+
+        let occurrences: Vec<Occurrence> = release
+            .scoped_changes()
+            .into_iter()
+            .flat_map(|(scope, changes)| {
+                changes.entries().map(move |(category, entry)| Occurrence {
+                    version,
+                    scope,
+                    category,
+                    entry,
+                })
+            })
+            .collect();
+
+        // The ends are matched before and after the starts of the same release, so an end closes a lifecycle
+        // from an earlier release first, but can also close one that starts in the same release,
+        // e.g. an api that is deprecated and removed at once, whatever the order of the categories.
+        let mut pending_ends = Vec::new();
+        for occurrence in &occurrences {
+            if let Some(key) = end_key(occurrence.category, occurrence.entry) {
+                match tracking.open(key) {
+                    Some(lifecycle) => lifecycle.end = Some(*occurrence),
+                    None => pending_ends.push((key, *occurrence)),
+                }
+            }
+        }
+
+        for occurrence in &occurrences {
+            if let Some(key) = start_key(occurrence.category, occurrence.entry) {
+                if tracking.open(key).is_none() {
+                    tracking.lifecycles.push(Lifecycle {
+                        key,
+                        start: *occurrence,
+                        end: None,
+                        releases_since: None,
+                    });
+                }
+            }
+        }
+
+        for (key, occurrence) in pending_ends {
+            match tracking.open(key) {
+                Some(lifecycle) => lifecycle.end = Some(occurrence),
+                None => tracking.unmatched_ends.push((key, occurrence)),
+            }
+        }
+    }
+
+    for lifecycle in &mut tracking.lifecycles {
+        if is_unreleased(lifecycle.start.version) {
+            continue;
+        }
+        let start = released_before[lifecycle.start.version] + 1;
+        let end = match &lifecycle.end {
+            Some(end) => released_before[end.version] + 1,
+            None => released_count,
+        };
+        lifecycle.releases_since = Some(end.saturating_sub(start));
+    }
+
+    tracking
+}
+
+/// Deprecated entries, linked to the later removed entries for the same api.
+/// Removed entries that drop an experimental feature do not need a deprecation.
+pub fn deprecations(document: &Document) -> Tracking<'_> {
+    let mut tracking = track(
+        document,
+        |category, entry| match category {
+            CategoryType::Deprecated => entry.api.as_deref(),
+            _ => None,
+        },
+        |category, entry| match category {
+            CategoryType::Removed => entry.api.as_deref(),
+            _ => None,
+        },
+    );
+
+    let experimental = experimental_features(document);
+    tracking.unmatched_ends.retain(|(key, removed)| {
+        !experimental.lifecycles.iter().any(|lifecycle| {
+            lifecycle.key == *key && lifecycle.end.is_some_and(|end| end.version == removed.version)
+        })
+    });
+    tracking
+}
+
+/// Workaround entries, linked to the later fixed or improved entries that resolve them.
+pub fn workarounds(document: &Document) -> Tracking<'_> {
+    track(
+        document,
+        |category, entry| match category {
+            CategoryType::Workaround => entry.id.as_deref(),
+            _ => None,
+        },
+        |category, entry| match category {
+            CategoryType::Fixed | CategoryType::Improved => entry.resolves.as_deref(),
+            _ => None,
+        },
+    )
+}
+
+/// Experimental entries, linked to the later added entries that stabilize them or removed entries that drop them.
+pub fn experimental_features(document: &Document) -> Tracking<'_> {
+    track(
+        document,
+        |category, entry| match category {
+            CategoryType::Experimental => entry.api.as_deref(),
+            _ => None,
+        },
+        |category, entry| match category {
+            CategoryType::Added | CategoryType::Removed => entry.api.as_deref(),
+            _ => None,
+        },
+    )
+}
+
+/// Known issues (`noted` entries), linked to the later fixed or improved entries that resolve them.
+pub fn known_issues(document: &Document) -> Tracking<'_> {
+    track(
+        document,
+        |category, entry| match category {
+            CategoryType::Noted => entry.id.as_deref(),
+            _ => None,
+        },
+        |category, entry| match category {
+            CategoryType::Fixed | CategoryType::Improved => entry.resolves.as_deref(),
+            _ => None,
+        },
+    )
+}
+
+/// A known issue from an earlier release that is still present.
+pub struct CarriedIssue {
+    pub text: String,
+    // Repo is the key in `repos` if the issue was noted under a repo.
+    pub repo: Option<String>,
+    pub noted_in: String,
+}
+
+/// The known issues carried forward into each release, by release. An issue with an id is carried into every
+/// newer release until the release that resolves it.
+pub fn carried_known_issues(document: &Document) -> HashMap<String, Vec<CarriedIssue>> {
+    let positions: HashMap<&str, usize> = document
+        .releases
+        .keys()
+        .enumerate()
+        .map(|(index, version)| (version.as_str(), index))
+        .collect();
+
+    let mut carried: HashMap<String, Vec<CarriedIssue>> = HashMap::new();
+    for lifecycle in &known_issues(document).lifecycles {
+        // The releases are listed newest first, so the newer releases have lower positions.
+        let first_newer = lifecycle.end.as_ref().map_or(0, |end| positions[end.version] + 1);
+        let start = positions[lifecycle.start.version];
+        for version in document.releases.keys().take(start).skip(first_newer) {
+            carried.entry(version.clone()).or_default().push(CarriedIssue {
+                text: lifecycle.start.entry.text.trim().to_string(),
+                repo: match lifecycle.start.scope {
+                    Scope::Repo(key) => Some(key.to_string()),
+                    _ => None,
+                },
+                noted_in: lifecycle.start.version.to_string(),
+            });
+        }
+    }
+    carried
+}
+
+/// A reference from an entry to a related entry in another release, e.g. "removed in v2.0.0".
+pub struct CrossReference {
+    pub label: &'static str,
+    pub version: String,
+}
+
+// Release, scope kind and name, category and text of an entry.
+type EntryKey = (String, &'static str, String, CategoryType, String);
+
+fn entry_key(version: &str, scope: Scope, category: CategoryType, entry: &Entry) -> EntryKey {
+    (
+        version.to_string(),
+        scope.kind(),
+        scope.name().to_string(),
+        category,
+        entry.text.clone(),
+    )
+}
+
+/// The cross references of the entries, by release, scope, category and entry text.
+#[derive(Default)]
+pub struct CrossReferences {
+    by_entry: HashMap<EntryKey, Vec<CrossReference>>,
+}
+
+impl CrossReferences {
+    fn add(&mut self, from: &Occurrence, label: &'static str, to_version: &str) {
+        self.by_entry
+            .entry(entry_key(from.version, from.scope, from.category, from.entry))
+            .or_default()
+            .push(CrossReference {
+                label,
+                version: to_version.to_string(),
+            });
+    }
+
+    /// Adds a reference in both directions for each lifecycle that has ended.
+    pub fn add_lifecycles(&mut self, tracking: &Tracking, start_label: &'static str, end_label: &'static str) {
+        for lifecycle in &tracking.lifecycles {
+            if let Some(end) = &lifecycle.end {
+                self.add(&lifecycle.start, end_label, end.version);
+                self.add(end, start_label, lifecycle.start.version);
+            }
+        }
+    }
+
+    pub fn get(&self, version: &str, scope: Scope, category: CategoryType, entry: &Entry) -> &[CrossReference] {
+        self.by_entry
+            .get(&entry_key(version, scope, category, entry))
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+/// All cross references that are rendered in the changelog.
+pub fn cross_references(document: &Document) -> CrossReferences {
+    let mut references = CrossReferences::default();
+    references.add_lifecycles(&deprecations(document), "deprecated in", "removed in");
+    references.add_lifecycles(&workarounds(document), "workaround in", "resolved in");
+    references.add_lifecycles(&known_issues(document), "known issue since", "resolved in");
+
+    // The label of an experimental feature depends on how it ended.
+    for lifecycle in &experimental_features(document).lifecycles {
+        if let Some(end) = &lifecycle.end {
+            let end_label = match end.category {
+                CategoryType::Removed => "dropped in",
+                _ => "stabilized in",
+            };
+            references.add(&lifecycle.start, end_label, end.version);
+            references.add(end, "experimental since", lifecycle.start.version);
+        }
+    }
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = r"
+repo: piot/nimble
+releases:
+  unreleased:
+    sections:
+      general:
+        changes:
+          deprecated:
+            - text: Old lobby api
+              api: Lobby::join
+  1.1.0:
+    date: 2024-02-01
+    sections:
+      general:
+        changes:
+          removed:
+            - text: Remove the sync api
+              api: sync
+          deprecated:
+            - text: Deprecate the sync api
+              api: sync
+  1.0.0:
+    date: 2024-01-01
+    sections:
+      general:
+        changes:
+          deprecated:
+            - text: Deprecate the chat api
+              api: chat
+";
+
+    #[test]
+    fn deprecated_and_removed_in_the_same_release() {
+        let document: Document = serde_yaml::from_str(DOCUMENT).unwrap();
+        let tracking = deprecations(&document);
+        assert!(tracking.unmatched_ends.is_empty());
+
+        let sync = tracking.lifecycles.iter().find(|lifecycle| lifecycle.key == "sync").unwrap();
+        assert_eq!(sync.start.version, "1.1.0");
+        assert_eq!(sync.end.map(|end| end.version), Some("1.1.0"));
+        assert_eq!(sync.releases_since, Some(0));
+    }
+
+    #[test]
+    fn releases_since_the_start() {
+        let document: Document = serde_yaml::from_str(DOCUMENT).unwrap();
+        let tracking = deprecations(&document);
+        let unresolved: Vec<_> = tracking
+            .unresolved()
+            .map(|lifecycle| (lifecycle.key, lifecycle.releases_since))
+            .collect();
+        assert_eq!(unresolved, vec![("chat", Some(1)), ("Lobby::join", None)]);
+    }
+
+    #[test]
+    fn cross_references_are_per_scope() {
+        let document: Document = serde_yaml::from_str(
+            r"
+repo: piot/nimble
+releases:
+  1.1.0:
+    date: 2024-02-01
+    sections:
+      server:
+        changes:
+          removed:
+            - text: Old connect api
+              api: Server::connect
+  1.0.0:
+    date: 2024-01-01
+    sections:
+      client:
+        changes:
+          deprecated:
+            - text: Old connect api
+              api: Client::connect
+      server:
+        changes:
+          deprecated:
+            - text: Old connect api
+              api: Server::connect
+",
+        )
+        .unwrap();
+        let references = cross_references(&document);
+        let deprecated = &document.releases["1.0.0"].sections.as_ref().unwrap()["client"].changes.deprecated;
+        let entry = &deprecated.as_ref().unwrap()[0];
+
+        assert!(references.get("1.0.0", Scope::Section("client"), CategoryType::Deprecated, entry).is_empty());
+        let server = references.get("1.0.0", Scope::Section("server"), CategoryType::Deprecated, entry);
+        assert_eq!(server.len(), 1);
+        assert_eq!((server[0].label, server[0].version.as_str()), ("removed in", "1.1.0"));
+    }
+
+    #[test]
+    fn known_issues_are_carried_until_resolved() {
+        let document: Document = serde_yaml::from_str(
+            r"
+repo: piot/nimble
+releases:
+  unreleased:
+    sections:
+      general:
+        changes:
+          fixed:
+            - text: Desync fixed
+              resolves: desync
+  1.2.0:
+    date: 2024-03-01
+  1.1.0:
+    date: 2024-02-01
+    sections:
+      general:
+        changes:
+          fixed:
+            - text: Fixed the crash
+              resolves: crash
+  1.0.0:
+    date: 2024-01-01
+    sections:
+      general:
+        changes:
+          noted:
+            - text: Crash on connect
+              id: crash
+            - text: Desync after reconnect
+              id: desync
+",
+        )
+        .unwrap();
+        let carried = carried_known_issues(&document);
+        let texts = |version: &str| -> Vec<String> {
+            carried
+                .get(version)
+                .map(|issues| issues.iter().map(|issue| issue.text.clone()).collect())
+                .unwrap_or_default()
+        };
+        assert_eq!(texts("1.0.0"), Vec::<String>::new());
+        assert_eq!(texts("1.1.0"), vec!["Desync after reconnect"]);
+        assert_eq!(texts("1.2.0"), vec!["Desync after reconnect"]);
+        assert_eq!(texts("unreleased"), Vec::<String>::new());
+        assert_eq!(carried["1.2.0"][0].noted_in, "1.0.0");
+    }
+}
@@ -0,0 +1,130 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use serde::Deserialize;
+
+pub const DEFAULT_TAG_TEMPLATE: &str = "{version}";
+
+/// Replaces each `{name}` in the template with its value.
+pub fn expand(template: &str, values: &[(&str, &str)]) -> String {
+    let mut result = template.to_string();
+    for (name, value) in values {
+        result = result.replace(&format!("{{{}}}", name), value);
+    }
+    result
+}
+
+/// Name of the git tag for a release, e.g. `nimble-{version}` gives `nimble-v1.2.0` for `v1.2.0`.
+pub fn tag_name(tag_template: Option<&str>, version: &str) -> String {
+    expand(
+        tag_template.unwrap_or(DEFAULT_TAG_TEMPLATE),
+        &[("version", version)],
+    )
+}
+
+/// The release key of a tag name, if the tag name follows the template.
+pub fn version_from_tag<'t>(tag_template: Option<&str>, tag: &'t str) -> Option<&'t str> {
+    let template = tag_template.unwrap_or(DEFAULT_TAG_TEMPLATE);
+    let (prefix, suffix) = template.split_once("{version}")?;
+    tag.strip_prefix(prefix)?
+        .strip_suffix(suffix)
+        .filter(|version| !version.is_empty())
+}
+
+/// URL templates for the generated links. `{repo}` is the `owner/name` of the repository.
+/// The defaults follow the url layout of the forge, see `Forge::url_templates`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UrlTemplates {
+    // Repository uses `{repo}`.
+    pub repository: String,
+
+    // Release uses `{repo}` and `{tag}`.
+    pub release: String,
+
+    // Download is where the assets of a release are, and uses `{repo}`, `{tag}` and `{name}`.
+    pub download: String,
+
+    // Compare uses `{repo}`, `{from}` and `{to}`. `{to}` is `HEAD` for unreleased changes.
+    pub compare: String,
+
+    // Pull request (or merge request) uses `{repo}` and `{number}`.
+    pub pull_request: String,
+
+    // Issue uses `{repo}` and `{number}`.
+    pub issue: String,
+
+    // Commit uses `{repo}` and `{hash}`.
+    pub commit: String,
+
+    // Profile uses `{user}`.
+    pub profile: String,
+}
+
+/// The `urls` in the document, each one replacing the template of the forge.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+pub struct UrlTemplateOverrides {
+    pub repository: Option<String>,
+    pub release: Option<String>,
+    pub download: Option<String>,
+    pub compare: Option<String>,
+    pub pull_request: Option<String>,
+    pub issue: Option<String>,
+    pub commit: Option<String>,
+    pub profile: Option<String>,
+}
+
+impl UrlTemplates {
+    pub fn with_overrides(self, overrides: Option<&UrlTemplateOverrides>) -> UrlTemplates {
+        let Some(overrides) = overrides else {
+            return self;
+        };
+        let pick = |template: &Option<String>, default: String| template.clone().unwrap_or(default);
+
+        UrlTemplates {
+            repository: pick(&overrides.repository, self.repository),
+            release: pick(&overrides.release, self.release),
+            download: pick(&overrides.download, self.download),
+            compare: pick(&overrides.compare, self.compare),
+            pull_request: pick(&overrides.pull_request, self.pull_request),
+            issue: pick(&overrides.issue, self.issue),
+            commit: pick(&overrides.commit, self.commit),
+            profile: pick(&overrides.profile, self.profile),
+        }
+    }
+
+    pub fn repository(&self, repo: &str) -> String {
+        expand(&self.repository, &[("repo", repo)])
+    }
+
+    pub fn release(&self, repo: &str, tag: &str) -> String {
+        expand(&self.release, &[("repo", repo), ("tag", tag)])
+    }
+
+    pub fn download(&self, repo: &str, tag: &str, name: &str) -> String {
+        expand(&self.download, &[("repo", repo), ("tag", tag), ("name", name)])
+    }
+
+    pub fn compare(&self, repo: &str, from: &str, to: &str) -> String {
+        expand(&self.compare, &[("repo", repo), ("from", from), ("to", to)])
+    }
+
+    pub fn pull_request(&self, repo: &str, number: usize) -> String {
+        expand(
+            &self.pull_request,
+            &[("repo", repo), ("number", &number.to_string())],
+        )
+    }
+
+    pub fn issue(&self, repo: &str, number: usize) -> String {
+        expand(&self.issue, &[("repo", repo), ("number", &number.to_string())])
+    }
+
+    pub fn commit(&self, repo: &str, hash: &str) -> String {
+        expand(&self.commit, &[("repo", repo), ("hash", hash)])
+    }
+
+    pub fn profile(&self, user: &str) -> String {
+        expand(&self.profile, &[("user", user)])
+    }
+}
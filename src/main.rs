@@ -2,23 +2,67 @@
  *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
  *  Licensed under the MIT License. See LICENSE in the project root for license information.
  *--------------------------------------------------------------------------------------------------------*/
-use std::{env, io};
-use std::path::PathBuf;
+use std::io::Read;
+use std::ops::Bound;
+use std::{env, fs, io, process};
+use std::path::{Path, PathBuf};
+use chrono::Local;
 use regex::Regex;
+use serde::Serialize;
 mod formatters;
+mod advisory;
+mod assets;
+mod autolink;
+mod diff;
+mod forge;
+mod git;
+mod history;
+mod lifecycle;
+mod links;
+mod migration;
+mod query;
+mod support;
+mod whats_new;
 
-use crate::formatter::{AdmonitionFormatter, AdmonitionType, EmojiFormatter, HeadingFormatter, LinkFormatter};
+use crate::args::Args;
+use crate::date::DateFormat;
+use crate::advisory::{advisories, advisory_suffix, osv_record, rustsec_draft};
+use crate::assets::{file_checksum, fill_asset_checksums, human_size, AssetChecksum};
+use crate::formatter::{AdmonitionFormatter, AdmonitionType, BadgeFormatter, DateFormatter, EmojiFormatter, HeadingFormatter, LinkDefinitionFormatter, LinkFormatter, StrikethroughFormatter, SuperFormatter, TableFormatter};
 use crate::formatters::ascii_doc::AsciiDocFormatter;
 use crate::formatters::markdown_github::MarkdownGitHubFormatter;
-use crate::yaml::Document;
+use crate::autolink::{replace_links, DocumentLinks, LinkContext};
+use crate::diff::{diff_documents, print_diff};
+use crate::git::{verify_commits, GitRepository, VerifiedCommits};
+use crate::lifecycle::{carried_known_issues, cross_references, deprecations, experimental_features, workarounds, CrossReference};
+use crate::links::{tag_name, UrlTemplates};
+use crate::migration::{migration_guide, print_migration_guide};
+use crate::query::Query;
+use crate::support::{expired_lines, support_lines};
+use crate::whats_new::{print_whats_new, whats_new};
+use crate::history::compare_history;
+use crate::release_tags::{check_release_tags, fill_release_dates};
+use crate::validate::{validate, ValidationError};
+use crate::version::{is_unreleased, parse_version, sort_releases_by_version, ReleaseVersion, VersionRange};
+use crate::yaml::{is_yanked, Document, Entry, Scope, Yanked};
 
+mod args;
+mod date;
 mod yaml;
 mod formatter;
 mod emoji;
+mod release_tags;
+mod validate;
+mod version;
 
-const GITHUB_URL_PREFIX: &str = "https://github.com/";
+// Same as the default of `git log --abbrev-commit` for small repositories.
+const DEFAULT_COMMIT_ABBREVIATION: usize = 7;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+// Git does not abbreviate a commit hash to fewer hex digits than this.
+const MIN_COMMIT_ABBREVIATION: usize = 4;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum CategoryType {
     Changed,
     Added,
@@ -50,7 +94,7 @@ fn string_to_admonition_type(name: &str) -> AdmonitionType {
     }
 }
 
-fn replace_admonition<F: AdmonitionFormatter>(line: &str, formatter: &F) -> String {
+fn replace_admonition<F: AdmonitionFormatter + ?Sized>(line: &str, formatter: &F) -> String {
     let re = Regex::new(r#"(WARNING|TIP|NOTE|IMPORTANT|CAUTION):\s.*"#).unwrap();
     let mut line_to_print = String::new();
     let mut previous_match_position = 0;
@@ -70,86 +114,13 @@ fn replace_admonition<F: AdmonitionFormatter>(line: &str, formatter: &F) -> Stri
 }
 
 
-fn replace_pull_request_link(
-    line: &str,
-    repo_short_url: &str,
-    formatter: &dyn LinkFormatter,
-) -> Result<String, Box<dyn std::error::Error>> {
-    let re = Regex::new(r"#\d*").unwrap();
-    let mut line_to_print = String::new();
-    let mut previous_match_position = 0;
-
-    for mat in re.find_iter(line) {
-        let match_str = mat.as_str();
-        let pull_request_id = match_str[1..].parse::<usize>()?;
-        let suffix = format!("pull/{}", pull_request_id);
-        let pull_request_link = format!("{}{}/{}", GITHUB_URL_PREFIX, repo_short_url, suffix);
-        let pull_request_complete_link =
-            formatter.link(&format!("#{}", pull_request_id), &pull_request_link);
-        line_to_print += &line[previous_match_position..mat.start()];
-        line_to_print += &pull_request_complete_link;
-        previous_match_position = mat.end();
-    }
-
-    line_to_print += &line[previous_match_position..];
-    Ok(line_to_print)
-}
-
-fn replace_commit_hash_link(
-    line: &str,
-    repo_short_url: &str,
-    formatter: &dyn LinkFormatter,
-) -> String {
-    let re = Regex::new(r"\$[a-f\d]*").unwrap();
-    let mut line_to_print = String::new();
-    let mut previous_match_position = 0;
-
-    for mat in re.find_iter(line) {
-        let match_str = mat.as_str();
-        let commit_hash_string = &match_str[1..];
-        let commit_hash_link = format!(
-            "{}{}/commit/{}",
-            GITHUB_URL_PREFIX, repo_short_url, commit_hash_string
-        );
-        let commit_hash_link_complete = formatter.link(commit_hash_string, &commit_hash_link);
-        line_to_print += &line[previous_match_position..mat.start()];
-        line_to_print += &commit_hash_link_complete;
-        previous_match_position = mat.end();
-    }
-
-    line_to_print += &line[previous_match_position..];
-    line_to_print
-}
-
-fn replace_at_profile_link<F: LinkFormatter>(line: &str, formatter: &F) -> String {
-    let re = Regex::new(r"@[\w-]*").unwrap();
-    let mut line_to_print = String::new();
-    let mut previous_match_position = 0;
-
-    for mat in re.find_iter(line) {
-        let match_str = mat.as_str();
-        let username_string = &match_str[1..]; // Trim "@" from the match
-        let username_profile_link = format!("{}{}", GITHUB_URL_PREFIX, username_string);
-        let username_profile_link_complete = formatter.link(match_str, &username_profile_link);
-        line_to_print += &line[previous_match_position..mat.start()];
-        line_to_print += &username_profile_link_complete;
-        previous_match_position = mat.end();
-    }
-
-    line_to_print += &line[previous_match_position..];
-    line_to_print
-}
-
-fn replace_line<F: LinkFormatter>(line: &str, repo_short_url: &str, formatter: &F) -> String {
-    let mut s = replace_at_profile_link(line, formatter);
-    s = replace_commit_hash_link(&s, repo_short_url, formatter);
-    replace_pull_request_link(&s, repo_short_url, formatter)
-        .expect("should work to format PR link")
+fn replace_line<F: LinkFormatter + ?Sized>(line: &str, links: &LinkContext, formatter: &F) -> String {
+    replace_links(line, links, formatter, false)
 }
 
-fn replace_notice<F: AdmonitionFormatter + LinkFormatter>(line: &str, formatter: &F) -> String {
+fn replace_notice<F: AdmonitionFormatter + LinkFormatter + ?Sized>(line: &str, links: &LinkContext, formatter: &F) -> String {
     let s = replace_admonition(line, formatter);
-    replace_at_profile_link(&s, formatter)
+    replace_links(&s, links, formatter, true)
 }
 
 fn description_from_category(category_type: &CategoryType) -> &'static str {
@@ -175,133 +146,298 @@ fn description_from_category(category_type: &CategoryType) -> &'static str {
     }
 }
 
-fn print_line<F: LinkFormatter + EmojiFormatter>(
-    repo_url: &str,
+/// The category of a key in `changes`, e.g. `fixed`.
+fn category_from_key(key: &str) -> Option<CategoryType> {
+    let category = match key {
+        "added" => CategoryType::Added,
+        "changed" => CategoryType::Changed,
+        "deprecated" => CategoryType::Deprecated,
+        "removed" => CategoryType::Removed,
+        "fixed" => CategoryType::Fixed,
+        "security" => CategoryType::Security,
+        "improved" => CategoryType::Improved,
+        "workaround" => CategoryType::Workaround,
+        "tests" => CategoryType::Tests,
+        "docs" => CategoryType::Docs,
+        "refactored" => CategoryType::Refactored,
+        "optimization" => CategoryType::Optimization,
+        "breaking" => CategoryType::Breaking,
+        "experimental" => CategoryType::Experimental,
+        "noted" => CategoryType::Noted,
+        "style" => CategoryType::Style,
+        "unreleased" => CategoryType::Unreleased,
+        "examples" => CategoryType::Examples,
+        _ => return None,
+    };
+    Some(category)
+}
+
+fn print_line<F: LinkFormatter + EmojiFormatter + ?Sized>(
+    links: &LinkContext,
     change_type: &CategoryType,
-    s: &String,
+    s: &str,
+    suffix: &str,
+    breaking: bool,
     formatter: &F,
 ) {
-    let replaced = replace_line(s.trim(), repo_url, formatter);
-    let description = description_from_category(change_type);
-    if change_type == &CategoryType::Breaking {
+    let replaced = replace_line(s.trim(), links, formatter) + suffix;
+    if breaking {
+        let description = description_from_category(&CategoryType::Breaking);
         println!("* {}[{}] {}", formatter.emoji(change_type), description, replaced);
     } else {
         println!("* {} {}", formatter.emoji(change_type), replaced);
     }
 }
 
-fn print_optional_list<F: LinkFormatter + EmojiFormatter>(
-    repo_url: &str,
+fn print_optional_list<F: LinkFormatter + EmojiFormatter + ?Sized>(
+    links: &LinkContext,
+    scope: Scope,
     change_type: CategoryType,
-    list: &Option<Vec<String>>,
+    list: &Option<Vec<Entry>>,
+    suffix: &EntrySuffix,
     formatter: &F,
 ) {
     if let Some(items) = list {
         for item in items {
-            print_line(repo_url, &change_type, item, formatter)
+            let breaking = item.is_breaking(change_type);
+            print_line(links, &change_type, &item.text, &suffix(scope, change_type, item), breaking, formatter)
         }
     }
 }
 
 
-fn print_changes<F: LinkFormatter + EmojiFormatter>(repo_url: &str, changes: &yaml::Changes, formatter: &F) {
-    print_optional_list(
-        repo_url,
-        CategoryType::Unreleased,
-        &changes.unreleased,
-        formatter,
-    );
-
-    print_optional_list(
-        repo_url,
-        CategoryType::Breaking,
-        &changes.breaking,
-        formatter,
-    );
+/// Suffix for an entry, e.g. the cross references to related entries in other releases.
+type EntrySuffix<'a> = dyn Fn(Scope, CategoryType, &Entry) -> String + 'a;
 
-    print_optional_list(repo_url, CategoryType::Added, &changes.added, formatter);
+fn print_changes<F: LinkFormatter + EmojiFormatter + ?Sized>(
+    links: &LinkContext,
+    scope: Scope,
+    changes: &yaml::Changes,
+    suffix: &EntrySuffix,
+    formatter: &F,
+) {
+    for (change_type, list) in changes.categories() {
+        if change_type == CategoryType::Examples {
+            continue;
+        }
+        print_optional_list(links, scope, change_type, list, suffix, formatter);
+    }
+}
 
-    print_optional_list(repo_url, CategoryType::Fixed, &changes.fixed, formatter);
+/// The breaking entries of all sections, packages and repos in the release, with their links.
+fn breaking_summary<F: LinkFormatter + ?Sized>(release: &yaml::Release, document_links: &DocumentLinks, formatter: &F) -> Vec<String> {
+    let mut items = Vec::new();
+    for (scope, changes) in release.scoped_changes() {
+        let links = match scope {
+            Scope::Repo(key) => document_links.repo(key),
+            _ => None,
+        }
+        .unwrap_or_else(|| document_links.document());
+        for (category, entry) in changes.entries() {
+            if entry.is_breaking(category) {
+                items.push(replace_line(entry.text.trim(), &links, formatter));
+            }
+        }
+    }
+    items
+}
 
-    print_optional_list(
-        repo_url,
-        CategoryType::Workaround,
-        &changes.workaround,
-        formatter,
-    );
+fn yanked_notice(what: &str, yanked: &Yanked) -> String {
+    match yanked.reason() {
+        Some(reason) => format!("This {} was yanked: {}", what, reason.trim()),
+        None => format!("This {} was yanked.", what),
+    }
+}
 
-    print_optional_list(repo_url, CategoryType::Changed, &changes.changed, formatter);
+fn head_link(repo: &str, urls: &UrlTemplates, latest_tag: Option<&str>) -> String {
+    match latest_tag {
+        Some(latest_tag) => urls.compare(repo, latest_tag, "HEAD"),
+        None => urls.repository(repo),
+    }
+}
 
-    print_optional_list(repo_url, CategoryType::Removed, &changes.removed, formatter);
+struct ReleaseLinks {
+    // Heading is the version name, linked to the release page, or to the changes since the latest tag if unreleased.
+    heading: String,
+    // Full diff links to the changes since the previous release, unless the heading already links there.
+    full_diff: Option<String>,
+    // Definition is the link reference definition that the heading refers to, for the formats that have them.
+    definition: Option<String>,
+}
 
-    print_optional_list(
-        repo_url,
-        CategoryType::Improved,
-        &changes.improved,
-        formatter,
-    );
+/// The links of a release heading. With `compare_links` a "Full diff" link is added, and the heading
+/// refers to a link reference definition, like in Keep a Changelog, if the format supports it.
+fn release_links<F: LinkFormatter + LinkDefinitionFormatter + ?Sized>(
+    version_name: &str,
+    tag: Option<&str>,
+    previous_tag: Option<&str>,
+    repo: &str,
+    urls: &UrlTemplates,
+    compare_links: bool,
+    formatter: &F,
+) -> ReleaseLinks {
+    let (link_to_version, full_diff_link) = match tag {
+        Some(tag) => (
+            urls.release(repo, tag),
+            previous_tag.map(|previous| urls.compare(repo, previous, tag)),
+        ),
+        None => (head_link(repo, urls, previous_tag), None),
+    };
+    if !compare_links {
+        return ReleaseLinks {
+            heading: formatter.link(version_name, &link_to_version),
+            full_diff: None,
+            definition: None,
+        };
+    }
 
-    print_optional_list(repo_url, CategoryType::Docs, &changes.docs, formatter);
+    let definition = formatter.link_definition(version_name, &link_to_version);
+    ReleaseLinks {
+        heading: match definition {
+            Some(_) => formatter.reference_link(version_name),
+            None => formatter.link(version_name, &link_to_version),
+        },
+        full_diff: full_diff_link.map(|link| formatter.link("Full diff", &link)),
+        definition,
+    }
+}
 
-    print_optional_list(repo_url, CategoryType::Tests, &changes.tests, formatter);
+pub struct RenderOptions {
+    // Adds a "Full diff" link to the changes since the previous release, and link reference definitions
+    // at the end of the document for the formats that support them.
+    pub compare_links: bool,
+    // Full hashes for the commits verified in a local clone, used in the commit links.
+    pub commits: VerifiedCommits,
+    // Number of hex digits shown for each commit hash. All digits are shown if not set.
+    pub commit_abbreviation: Option<usize>,
+    // Releases are the releases that are rendered. The other releases are still used for the compare links.
+    pub releases: VersionRange,
+    // Heading is the top-level "Changelog" heading, left out when the output is used as a release body.
+    pub heading: bool,
+}
 
-    print_optional_list(
-        repo_url,
-        CategoryType::Refactored,
-        &changes.refactored,
-        formatter,
-    );
+fn print_document<F: AdmonitionFormatter + LinkFormatter + LinkDefinitionFormatter + HeadingFormatter + EmojiFormatter + DateFormatter + StrikethroughFormatter + BadgeFormatter + TableFormatter + ?Sized>(
+    deserialized: Document,
+    options: &RenderOptions,
+    formatter: &F,
+) {
+    if options.heading {
+        println!("{}", formatter.heading(1, "Changelog"));
+    }
 
-    print_optional_list(
-        repo_url,
-        CategoryType::Deprecated,
-        &changes.deprecated,
-        formatter,
-    );
+    let repo = &deserialized.repo;
+    let document_links = DocumentLinks::new(&deserialized, &options.commits, options.commit_abbreviation);
+    let links = document_links.document();
+    let urls = links.urls;
+    let scheme = deserialized.versioning.unwrap_or_default();
+    let tag_template = deserialized.tag.as_deref();
+    let release_tags: Vec<String> = deserialized
+        .releases
+        .keys()
+        .map(|version| tag_name(tag_template, version))
+        .collect();
+    let mut link_definitions = Vec::new();
 
-    print_optional_list(
-        repo_url,
-        CategoryType::Experimental,
-        &changes.experimental,
-        formatter,
-    );
+    let cross_references = cross_references(&deserialized);
+    let known_issues = carried_known_issues(&deserialized);
+    let latest_tag = deserialized
+        .releases
+        .keys()
+        .position(|version| !is_unreleased(version))
+        .map(|index| release_tags[index].as_str());
 
-    print_optional_list(repo_url, CategoryType::Noted, &changes.noted, formatter);
+    // The support table is part of the complete changelog, not of a single release body.
+    if options.heading {
+        // The rendered status does not depend on the day it is rendered, `check-support` reports expired lines.
+        let lines = support_lines(&deserialized, None);
+        if !lines.is_empty() {
+            let rows: Vec<Vec<String>> = lines
+                .iter()
+                .map(|line| {
+                    let latest = line.latest.map_or_else(String::new, |version| {
+                        formatter.link(version, &urls.release(repo, &tag_name(tag_template, version)))
+                    });
+                    let eol = line.line.eol.as_ref().map_or_else(String::new, |eol| formatter.date(eol));
+                    vec![line.key.to_string(), latest, line.status.description().to_string(), eol]
+                })
+                .collect();
+            println!("\n{}\n", formatter.heading(2, "Supported versions"));
+            println!("{}", formatter.table(&["Version", "Latest release", "Status", "End of life"], &rows));
+        }
+    }
 
-    print_optional_list(
-        repo_url,
-        CategoryType::Optimization,
-        &changes.optimization,
-        formatter,
-    );
+    let reference_link = |reference: &CrossReference| {
+        let (name, link) = match is_unreleased(&reference.version) {
+            true => ("Unreleased", head_link(repo, urls, latest_tag)),
+            false => (reference.version.as_str(), urls.release(repo, &tag_name(tag_template, &reference.version))),
+        };
+        format!(" ({} {})", reference.label, formatter.link(name, &link))
+    };
 
-    print_optional_list(repo_url, CategoryType::Style, &changes.style, formatter);
+    for (index, (release_version, release)) in deserialized.releases.into_iter().enumerate() {
+        if !options.releases.contains(&release_version) {
+            continue;
+        }
+        let release_key = release_version.clone();
+        let suffix = |scope: Scope, category: CategoryType, entry: &Entry| -> String {
+            let advisory = entry
+                .advisory
+                .as_ref()
+                .map(|advisory| advisory_suffix(advisory, &release_key, scheme, formatter))
+                .unwrap_or_default();
+            let references: String = cross_references
+                .get(&release_key, scope, category, entry)
+                .iter()
+                .map(reference_link)
+                .collect();
+            advisory + &references
+        };
+        let previous_tag = release_tags.get(index + 1);
+        let unreleased = is_unreleased(&release_version);
+        let version_name = match unreleased {
+            true => "Unreleased".to_string(),
+            false => release_version,
+        };
+        let release_links = release_links(
+            &version_name,
+            (!unreleased).then(|| release_tags[index].as_str()),
+            previous_tag.map(String::as_str),
+            repo,
+            urls,
+            options.compare_links,
+            formatter,
+        );
+        link_definitions.extend(release_links.definition);
+        let mut version_link = release_links.heading;
 
-    print_optional_list(
-        repo_url,
-        CategoryType::Security,
-        &changes.security,
-        formatter,
-    );
-}
+        let yanked = is_yanked(&release.yanked);
+        if yanked {
+            version_link = formatter.strikethrough(&version_link);
+        }
+        let mut heading = format!("{} {}", formatter.emoji_tag(), version_link);
+        if let Some(date) = &release.date {
+            heading += &format!(" ({})", formatter.date(date));
+        }
+        if yanked {
+            heading += " [YANKED]";
+        }
+        println!("\n{}\n", formatter.heading(2, &heading));
 
-fn print_document<F: AdmonitionFormatter + LinkFormatter + HeadingFormatter + EmojiFormatter>(deserialized: Document, formatter: &F) {
-    println!("{}", formatter.heading(1, "Changelog"));
+        if let Some(full_diff) = release_links.full_diff {
+            println!("{}\n", full_diff);
+        }
 
-    for (release_version, release) in deserialized.releases {
-        let link_to_version = format!(
-            "https://github.com/{}/releases/tag/{}",
-            deserialized.repo, release_version
-        );
+        if let (true, Some(yanked)) = (yanked, &release.yanked) {
+            println!("{}\n", formatter.admonition(AdmonitionType::Warning, &yanked_notice("release", yanked)));
+        }
 
-        let heading = format!("{} {} ({})",
-                              formatter.emoji_tag(),
-                              formatter.link(&release_version, &link_to_version),
-                              release.date);
-        println!("\n{}\n", formatter.heading(2, &heading));
+        let breaking = breaking_summary(&release, &document_links, formatter);
+        if !breaking.is_empty() {
+            println!("{}\n", formatter.admonition_list(AdmonitionType::Warning, "Breaking changes", &breaking));
+        }
 
         if let Some(notice) = release.notice {
-            println!("{}", replace_notice(notice.trim(), formatter));
+            println!("{}", replace_notice(notice.trim(), &links, formatter));
         }
 
         if let Some(sections) = release.sections {
@@ -309,21 +445,43 @@ fn print_document<F: AdmonitionFormatter + LinkFormatter + HeadingFormatter + Em
                 println!("\n{}\n", formatter.heading(3, section_name.trim()));
 
                 if let Some(notice) = section.notice {
-                    println!("{}\n", replace_notice(notice.trim(), formatter));
+                    println!("{}\n", replace_notice(notice.trim(), &links, formatter));
                 }
                 eprintln!("{:?}", section.changes);
 
-                print_changes(&deserialized.repo, &section.changes, formatter);
+                print_changes(&links, Scope::Section(&section_name), &section.changes, &suffix, formatter);
             }
         }
 
         if let Some(packages) = release.packages {
             for (package_name, changes) in &packages {
                 let repo_link = PathBuf::new().join("https://crates.io/crates/").join(package_name);
-                let link = formatter.link(&*package_name, repo_link.to_str().unwrap());
-                println!("\n{}\n", formatter.heading(3, &*link));
+                let mut link = formatter.link(package_name, repo_link.to_str().unwrap());
+                let package_yanked = is_yanked(&changes.yanked);
+                if package_yanked {
+                    link = formatter.strikethrough(&link);
+                }
+
+                let package_tag_template = deserialized
+                    .packages
+                    .as_ref()
+                    .and_then(|infos| infos.get(package_name))
+                    .and_then(|info| info.tag.as_deref());
+                if let (Some(package_tag_template), false) = (package_tag_template, unreleased) {
+                    let package_tag = tag_name(Some(package_tag_template), &version_name);
+                    let package_release_link = urls.release(repo, &package_tag);
+                    link += &format!(" ({})", formatter.link(&package_tag, &package_release_link));
+                }
+                if package_yanked {
+                    link += " [YANKED]";
+                }
+                println!("\n{}\n", formatter.heading(3, &link));
+
+                if let (true, Some(yanked)) = (package_yanked, &changes.yanked) {
+                    println!("{}\n", formatter.admonition(AdmonitionType::Warning, &yanked_notice("package release", yanked)));
+                }
 
-                print_changes(&deserialized.repo, &changes, formatter);
+                print_changes(&links, Scope::Package(package_name), changes, &suffix, formatter);
             }
         }
 
@@ -331,7 +489,8 @@ fn print_document<F: AdmonitionFormatter + LinkFormatter + HeadingFormatter + Em
             if let Some(dependency_repos) = release.repos {
                 for (repo_name, changes_in_repo) in dependency_repos {
                     let info = &repos[&repo_name];
-                    let repo_url = format!("{}{}", GITHUB_URL_PREFIX, info.repo);
+                    let links_in_repo = document_links.repo(&repo_name).expect("repos are validated");
+                    let repo_url = links_in_repo.urls.repository(&info.repo);
                     let link = formatter.link(&repo_name, &repo_url);
                     let mut description: String = "".to_string();
 
@@ -343,30 +502,867 @@ fn print_document<F: AdmonitionFormatter + LinkFormatter + HeadingFormatter + Em
 
                     println!("\n{}\n", formatter.heading(3, complete_line.trim()));
 
-                    print_changes(&info.repo, &changes_in_repo, formatter);
+                    print_changes(&links_in_repo, Scope::Repo(&repo_name), &changes_in_repo, &suffix, formatter);
                 }
             }
-        } else {
-            continue;
+        }
+
+        if let Some(issues) = known_issues.get(&release_key) {
+            println!("\n{}\n", formatter.heading(3, "Known issues"));
+            for issue in issues {
+                let links_for_issue = issue
+                    .repo
+                    .as_ref()
+                    .and_then(|key| document_links.repo(key))
+                    .unwrap_or_else(|| document_links.document());
+                let noted_in = CrossReference {
+                    label: "noted in",
+                    version: issue.noted_in.clone(),
+                };
+                print_line(&links_for_issue, &CategoryType::Noted, &issue.text, &reference_link(&noted_in), false, formatter);
+            }
+        }
+
+        if let Some(assets) = &release.assets {
+            let rows: Vec<Vec<String>> = assets
+                .iter()
+                .map(|asset| {
+                    let file_name = asset.file_name(&release_key);
+                    vec![
+                        formatter.link(&file_name, &asset.url(repo, urls, &release_key, &release_tags[index])),
+                        asset.size.map(human_size).unwrap_or_default(),
+                        asset.sha256.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            println!("\n{}\n", formatter.heading(3, "Downloads"));
+            println!("{}", formatter.table(&["File", "Size", "SHA-256"], &rows));
+        }
+    }
+
+    if !link_definitions.is_empty() {
+        println!();
+        for definition in link_definitions {
+            println!("{}", definition);
         }
     }
 }
 
-fn main() {
-    eprintln!("Accepting input from stdin");
-    let stdin = io::stdin();
-    let reader = stdin.lock();
-    let deserialized: Document = serde_yaml::from_reader(reader).unwrap();
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("error: {}", message);
+    process::exit(1);
+}
+
+fn exit_on_errors(errors: &[ValidationError]) {
+    if !errors.is_empty() {
+        for err in errors {
+            eprintln!("error: {}", err);
+        }
+        process::exit(1);
+    }
+}
+
+fn read_document_text(args: &Args) -> String {
+    match args.option("file") {
+        Some(path) => fs::read_to_string(path).unwrap_or_else(|err| fail(format!("{}: {}", path, err))),
+        None => {
+            eprintln!("Accepting input from stdin");
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text).unwrap_or_else(|err| fail(err));
+            text
+        }
+    }
+}
+
+fn open_git_repository(args: &Args) -> Option<GitRepository> {
+    args.option("git")
+        .map(|path| GitRepository::open(Path::new(path)).unwrap_or_else(|err| fail(err)))
+}
+
+/// Validates the document, and with `--git` also the commits and the release tags.
+fn check(document: &Document, args: &Args) {
+    let mut errors = validate(document);
+    if let Some(repository) = open_git_repository(args) {
+        let (_, commit_errors) = verify_commits(document, &repository).unwrap_or_else(|err| fail(err));
+        errors.extend(commit_errors);
+
+        let tags = repository.tags().unwrap_or_else(|err| fail(err));
+        let report = check_release_tags(document, &tags);
+        errors.extend(report.problems);
+        for (name, date) in report.missing_dates {
+            errors.push(ValidationError {
+                location: name,
+                message: format!("date is missing, the tag is from {}", date),
+            });
+        }
+    }
+    exit_on_errors(&errors);
+}
+
+/// Writes the tag date into each tagged release that has no date.
+fn fill_dates(text: &str, document: &Document, args: &Args) {
+    let repository = open_git_repository(args).unwrap_or_else(|| fail("fill-dates requires --git <path>"));
+    let tags = repository.tags().unwrap_or_else(|err| fail(err));
+    let report = check_release_tags(document, &tags);
+    for problem in &report.problems {
+        eprintln!("warning: {}", problem);
+    }
+
+    let filled = fill_release_dates(text, &report.missing_dates).unwrap_or_else(|err| fail(err));
+    match args.option("file") {
+        Some(path) => fs::write(path, filled).unwrap_or_else(|err| fail(format!("{}: {}", path, err))),
+        None => print!("{}", filled),
+    }
+    for (name, date) in &report.missing_dates {
+        eprintln!("filled {} with {}", name, date);
+    }
+}
+
+/// Writes the size and SHA-256 checksum of the files in `--dir` into the assets of a release.
+/// The release defaults to the latest released version.
+fn fill_assets(text: &str, document: &Document, args: &Args) {
+    let dir = args.option("dir").unwrap_or_else(|| fail("fill-assets requires --dir <path>"));
+    let version = match args.positional.get(1) {
+        Some(version) => version,
+        None => document
+            .latest_release(args.flag("include-yanked"))
+            .unwrap_or_else(|| fail("fill-assets requires a released version")),
+    };
+    let release = document
+        .releases
+        .get(version)
+        .unwrap_or_else(|| fail(format!("release '{}' is not found", version)));
+    let assets = release
+        .assets
+        .as_ref()
+        .unwrap_or_else(|| fail(format!("release '{}' has no assets", version)));
+
+    let mut checksums = Vec::new();
+    for asset in assets {
+        let path = Path::new(dir).join(asset.file_name(version));
+        let (size, sha256) = file_checksum(&path).unwrap_or_else(|err| fail(format!("{}: {}", path.display(), err)));
+        if asset.size != Some(size) || asset.sha256.as_ref() != Some(&sha256) {
+            eprintln!("filled {} with {} bytes, sha256 {}", path.display(), size, sha256);
+        }
+        checksums.push(AssetChecksum {
+            name: asset.name.clone(),
+            size,
+            sha256,
+        });
+    }
+
+    let filled = fill_asset_checksums(text, version, &checksums).unwrap_or_else(|err| fail(err));
+    match args.option("file") {
+        Some(path) => fs::write(path, filled).unwrap_or_else(|err| fail(format!("{}: {}", path, err))),
+        None => print!("{}", filled),
+    }
+}
+
+fn read_document(path: &str) -> Document {
+    let text = fs::read_to_string(path).unwrap_or_else(|err| fail(format!("{}: {}", path, err)));
+    serde_yaml::from_str(&text).unwrap_or_else(|err| fail(format!("{}: {}", path, err)))
+}
+
+/// The document in `file` as it is at a ref in the repository.
+fn document_at_ref(repository: &GitRepository, file: &str, git_ref: &str) -> Document {
+    let text = repository
+        .path_in_repository(Path::new(file))
+        .and_then(|path| repository.show_file(git_ref, &path))
+        .unwrap_or_else(|err| fail(err));
+    serde_yaml::from_str(&text).unwrap_or_else(|err| fail(format!("{} at {}: {}", file, git_ref, err)))
+}
+
+/// Fails if a published release differs from the same document at `--base <ref>`.
+fn check_history(document: &Document, args: &Args) {
+    let repository = open_git_repository(args).unwrap_or_else(|| fail("check-history requires --git <path>"));
+    let base_ref = args.option("base").unwrap_or_else(|| fail("check-history requires --base <ref>"));
+    let file = args.option("file").unwrap_or_else(|| fail("check-history requires --file <path>"));
+
+    let base = document_at_ref(&repository, file, base_ref);
+    let report = compare_history(&base, document, args.flag("allow-history-changes"));
+    for change in &report.warnings {
+        eprintln!("warning: {}", change);
+    }
+    exit_on_errors(&report.errors);
+}
+
+/// Shows what changed between two documents, either `diff <old> <new>` or the `--file` at `--base <ref>`
+/// compared to `--head <ref>`, or to the working tree if `--head` is not set.
+fn diff(args: &Args) {
+    let (old, new) = match &args.positional[1..] {
+        [old_path, new_path] => (read_document(old_path), read_document(new_path)),
+        [] => {
+            let repository = open_git_repository(args).unwrap_or_else(|| fail("diff requires two files or --git <path>"));
+            let base_ref = args.option("base").unwrap_or_else(|| fail("diff requires --base <ref>"));
+            let file = args.option("file").unwrap_or_else(|| fail("diff requires --file <path>"));
+            let new = match args.option("head") {
+                Some(head_ref) => document_at_ref(&repository, file, head_ref),
+                None => read_document(file),
+            };
+            (document_at_ref(&repository, file, base_ref), new)
+        }
+        _ => fail("diff requires either two files or --git <path> --base <ref> --file <path>"),
+    };
+    exit_on_errors(&validate(&new));
+
+    let changelog_diff = diff_documents(&old, &new);
+    if args.option("format") == Some("json") {
+        let json = serde_json::to_string_pretty(&changelog_diff).unwrap_or_else(|err| fail(err));
+        println!("{}", json);
+        return;
+    }
+    print_diff(&changelog_diff, &new, output_formatter(args).as_ref());
+}
+
+fn parse_version_option(args: &Args, name: &str, document: &Document) -> Option<ReleaseVersion> {
+    let scheme = document.versioning.unwrap_or_default();
+    args.option(name)
+        .map(|version| parse_version(version, scheme).unwrap_or_else(|err| fail(format!("--{}: {}", name, err))))
+}
+
+/// The releases selected with `--version`, `--latest`, `--since`/`--until` (inclusive) or `--after`/`--before` (exclusive).
+fn version_range(args: &Args, document: &Document) -> VersionRange {
+    let mut range = VersionRange::new(document.versioning.unwrap_or_default());
+
+    let include_yanked = args.flag("include-yanked");
+    let latest = args.flag("latest").then(|| {
+        let name = document
+            .latest_release(include_yanked)
+            .unwrap_or_else(|| fail("--latest requires a released version"));
+        parse_version(name, range.scheme).unwrap_or_else(|err| fail(err))
+    });
+    let exact = match (latest, parse_version_option(args, "version", document)) {
+        (Some(_), Some(_)) => fail("use either --latest or --version"),
+        (latest, version) => latest.or(version),
+    };
+    if let Some(version) = &exact {
+        let is_found = document
+            .releases
+            .keys()
+            .filter_map(|name| parse_version(name, range.scheme).ok())
+            .any(|name| name.cmp_precedence(version).is_eq());
+        if !is_found {
+            fail(format!("release '{}' is not found", version));
+        }
+        range.lower = Bound::Included(version.clone());
+        range.upper = Bound::Included(version.clone());
+    }
+
+    let lower = match (parse_version_option(args, "since", document), parse_version_option(args, "after", document)) {
+        (Some(_), Some(_)) => fail("use either --since or --after"),
+        (Some(since), None) => Some(Bound::Included(since)),
+        (None, Some(after)) => Some(Bound::Excluded(after)),
+        (None, None) => None,
+    };
+    let upper = match (parse_version_option(args, "until", document), parse_version_option(args, "before", document)) {
+        (Some(_), Some(_)) => fail("use either --until or --before"),
+        (Some(until), None) => Some(Bound::Included(until)),
+        (None, Some(before)) => Some(Bound::Excluded(before)),
+        (None, None) => None,
+    };
+    if exact.is_some() && (lower.is_some() || upper.is_some()) {
+        fail("--version and --latest can not be combined with a version range");
+    }
+    // A yanked release is only in a range if it is asked for, by `--version` or `--include-yanked`.
+    if (lower.is_some() || upper.is_some()) && !include_yanked {
+        range.excluded = yanked_releases(document);
+    }
+    if let Some(lower) = lower {
+        range.lower = lower;
+    }
+    if let Some(upper) = upper {
+        range.upper = upper;
+    }
+    range
+}
+
+fn yanked_releases(document: &Document) -> Vec<String> {
+    document
+        .releases
+        .iter()
+        .filter(|(_, release)| is_yanked(&release.yanked))
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+/// Prints the entries that match all the given filters, with their release and date.
+fn query(document: &Document, args: &Args) {
+    exit_on_errors(&validate(document));
+
+    let pattern = match (args.option("text"), args.option("regex")) {
+        (Some(_), Some(_)) => fail("use either --text or --regex"),
+        (Some(text), None) => Some(format!("(?i){}", regex::escape(text))),
+        (None, Some(pattern)) => Some(pattern.to_string()),
+        (None, None) => None,
+    };
+    let pattern = pattern.map(|pattern| {
+        Regex::new(&pattern).unwrap_or_else(|err| fail(format!("--regex '{}' is invalid: {}", pattern, err)))
+    });
+    let category = args.option("category").map(|key| {
+        category_from_key(key).unwrap_or_else(|| fail(format!("--category '{}' is not a known category", key)))
+    });
+    let pull_request = args.option("pr").map(|number| {
+        number
+            .trim_start_matches('#')
+            .parse::<usize>()
+            .unwrap_or_else(|_| fail(format!("--pr must be a number, not '{}'", number)))
+    });
+
+    let query = Query {
+        pattern,
+        category,
+        range: version_range(args, document),
+        section: args.option("section").map(str::to_string),
+        package: args.option("package").map(str::to_string),
+        repo: args.option("repo").map(str::to_string),
+        author: args.option("author").map(|author| author.trim_start_matches('@').to_string()),
+        pull_request,
+    };
+    let matches = query.run(document);
+
+    if args.option("format") == Some("json") {
+        let json = serde_json::to_string_pretty(&matches).unwrap_or_else(|err| fail(err));
+        println!("{}", json);
+        return;
+    }
+
+    for found in &matches {
+        let date = found.date.as_deref().unwrap_or("not released");
+        println!(
+            "{} ({}) {} {} [{}] {}",
+            found.version,
+            date,
+            found.scope,
+            found.name,
+            description_from_category(&found.category),
+            found.entry
+        );
+    }
+    if matches.is_empty() {
+        eprintln!("no matching entries");
+    }
+}
+
+/// Prints all changes after the installed version up to and including the target version as one release.
+/// The target defaults to the latest released version.
+fn whats_new_since(document: &Document, args: &Args) {
+    exit_on_errors(&validate(document));
+
+    let (installed, target, range) = whats_new_range(document, args);
+    let new = whats_new(document, &range);
+    if new.categories.is_empty() {
+        eprintln!("no changes between {} and {}", installed, target);
+    }
+
+    let title = format!("What's new in {} since {}", target, installed);
+    print_whats_new(&new, document, &title, output_formatter(args).as_ref());
+}
+
+/// The installed and the target version of `whats-new`, and the range of releases between them.
+/// The target defaults to the latest release that is not yanked, or the latest release with `--include-yanked`.
+fn whats_new_range<'a>(document: &'a Document, args: &'a Args) -> (&'a str, &'a str, VersionRange) {
+    let (installed, target) = match &args.positional[1..] {
+        [installed] => (installed, None),
+        [installed, target] => (installed, Some(target)),
+        _ => fail("whats-new requires <installed version> [<target version>]"),
+    };
+    let scheme = document.versioning.unwrap_or_default();
+    let parse = |version: &str| parse_version(version, scheme).unwrap_or_else(|err| fail(err));
+    let include_yanked = args.flag("include-yanked");
+    let target = target.or_else(|| document.latest_release(include_yanked));
+    let target = target.unwrap_or_else(|| fail("whats-new requires a released version"));
+
+    let mut range = VersionRange::new(scheme);
+    range.lower = Bound::Excluded(parse(installed));
+    range.upper = Bound::Included(parse(target));
+    (installed, target, range)
+}
+
+/// Prints an upgrade document with the migration notes of the releases selected with the version range options.
+fn print_upgrade_guide(document: &Document, args: &Args) {
+    exit_on_errors(&validate(document));
+
+    // The entries of yanked releases are still steps, only listed under the release after them.
+    let mut range = version_range(args, document);
+    let yanked = std::mem::take(&mut range.excluded);
+    let releases = migration_guide(document, &range, &yanked);
+    if releases.is_empty() {
+        eprintln!("no breaking, removed or deprecated entries");
+    }
+
+    let heading = !args.flag("no-heading");
+    print_migration_guide(&releases, document, heading, output_formatter(args).as_ref());
+}
+
+fn releases_ago(count: Option<usize>) -> String {
+    match count {
+        None => "not released yet".to_string(),
+        Some(0) => "in the latest release".to_string(),
+        Some(1) => "1 release ago".to_string(),
+        Some(count) => format!("{} releases ago", count),
+    }
+}
+
+/// Lists the deprecated apis that are not removed yet, and the removed apis that were never deprecated.
+fn deprecation_report(document: &Document) {
+    exit_on_errors(&validate(document));
+    let tracking = deprecations(document);
+
+    println!("Deprecated, not removed yet:");
+    for lifecycle in tracking.unresolved() {
+        let start = &lifecycle.start;
+        println!(
+            "  {}: deprecated in {} ({}), {}",
+            lifecycle.key,
+            start.version,
+            start.scope,
+            releases_ago(lifecycle.releases_since)
+        );
+    }
+
+    println!("Removed without an earlier deprecation:");
+    for (key, end) in &tracking.unmatched_ends {
+        println!("  {}: removed in {} ({})", key, end.version, end.scope);
+    }
+}
+
+/// Lists the workarounds that are not resolved yet. With `--max-releases <n>` it fails if a workaround
+/// has been unresolved for more than n releases.
+fn workaround_report(document: &Document, args: &Args) {
+    exit_on_errors(&validate(document));
+    let max_releases = args.option("max-releases").map(|count| {
+        count
+            .parse::<usize>()
+            .unwrap_or_else(|_| fail(format!("--max-releases must be a number, not '{}'", count)))
+    });
+
+    let tracking = workarounds(document);
+    let mut errors = Vec::new();
+    println!("Unresolved workarounds:");
+    for lifecycle in tracking.unresolved() {
+        let start = &lifecycle.start;
+        println!(
+            "  {}: {} in {} ({}), {}",
+            lifecycle.key,
+            start.entry.text.trim(),
+            start.version,
+            start.scope,
+            releases_ago(lifecycle.releases_since)
+        );
+        if max_releases.zip(lifecycle.releases_since).is_some_and(|(max, since)| since > max) {
+            errors.push(ValidationError {
+                location: start.version.to_string(),
+                message: format!(
+                    "workaround '{}' is still unresolved, it was added {}",
+                    lifecycle.key,
+                    releases_ago(lifecycle.releases_since)
+                ),
+            });
+        }
+    }
+    exit_on_errors(&errors);
+}
+
+/// Lists the experimental features that are neither stabilized nor dropped yet, and for how long.
+fn experimental_report(document: &Document) {
+    exit_on_errors(&validate(document));
+    let today = Local::now().date_naive();
+
+    println!("Experimental, not stabilized or dropped yet:");
+    for lifecycle in experimental_features(document).unresolved() {
+        let start = &lifecycle.start;
+        let mut age = releases_ago(lifecycle.releases_since);
+        if let Some(date) = document.releases[start.version].date {
+            age += &format!(", {} days", (today - date).num_days());
+        }
+        println!("  {}: experimental since {} ({}), {}", lifecycle.key, start.version, start.scope, age);
+    }
+}
+
+/// Lists the release lines with their support status, and warns about supported lines that are past their end of life.
+/// With `--strict` the warnings are errors.
+fn support_report(document: &Document, args: &Args) {
+    exit_on_errors(&validate(document));
+    let today = Local::now().date_naive();
+
+    for line in support_lines(document, Some(today)) {
+        let mut details = vec![line.status.description().to_string()];
+        if let Some(latest) = line.latest {
+            details.push(format!("latest {}", latest));
+        }
+        if let Some(active_until) = line.line.active_until {
+            details.push(format!("active until {}", active_until));
+        }
+        if let Some(eol) = line.line.eol {
+            details.push(format!("end of life {}", eol));
+        }
+        println!("{}: {}", line.key, details.join(", "));
+    }
 
-    let args: Vec<String> = env::args().collect();
-    match args.get(1).map(String::as_str) {
-        Some("asciidoc") => {
-            let formatter = AsciiDocFormatter {};
-            print_document(deserialized, &formatter);
+    let expired = expired_lines(document, today);
+    if args.flag("strict") {
+        exit_on_errors(&expired);
+    } else {
+        for line in &expired {
+            eprintln!("warning: {}", line);
+        }
+    }
+}
+
+/// Lists the security advisories, or exports them with `--format osv` as OSV JSON records
+/// or with `--format rustsec` as drafts for the RustSec advisory database. `--id` selects a single advisory.
+fn advisory_report(document: &Document, args: &Args) {
+    exit_on_errors(&validate(document));
+    let id = args.option("id");
+    let found = advisories(document, id);
+    if let (Some(id), true) = (id, found.is_empty()) {
+        fail(format!("advisory '{}' is not found", id));
+    }
+    let today = Local::now().date_naive();
+
+    match args.option("format") {
+        Some("osv") => {
+            let document_links = DocumentLinks::without_commits(document);
+            let urls = document_links.document().urls;
+            let records: Vec<_> = found
+                .iter()
+                .map(|published| osv_record(published, document, urls, today).unwrap_or_else(|err| fail(err)))
+                .collect();
+            // A single advisory is printed as one record, the way OSV databases store them.
+            let json = match (id, records.as_slice()) {
+                (Some(_), [record]) => serde_json::to_string_pretty(record),
+                _ => serde_json::to_string_pretty(&records),
+            }
+            .unwrap_or_else(|err| fail(err));
+            println!("{}", json);
+        }
+        Some("rustsec") => {
+            let drafts: Vec<String> = found
+                .iter()
+                .map(|published| rustsec_draft(published, document.versioning.unwrap_or_default(), today).unwrap_or_else(|err| fail(err)))
+                .collect();
+            println!("{}", drafts.join("\n\n"));
         }
         _ => {
-            let formatter = MarkdownGitHubFormatter {};
-            print_document(deserialized, &formatter);
+            for published in &found {
+                let severity = published
+                    .advisory
+                    .severity
+                    .map(|severity| format!(" [{}]", severity.name()))
+                    .unwrap_or_default();
+                println!(
+                    "{} in {} ({}){}: {}",
+                    published.advisory.ids.join(", "),
+                    published.version,
+                    published.package.unwrap_or("no package"),
+                    severity,
+                    published.summary()
+                );
+            }
+        }
+    }
+}
+
+/// The formatter for `--format`, or for `asciidoc` as the first argument, using `--date-format` and `--locale`.
+fn output_formatter(args: &Args) -> Box<dyn SuperFormatter> {
+    let date_format = DateFormat::new(args.option("date-format"), args.option("locale"))
+        .unwrap_or_else(|err| fail(err));
+    match args.option("format").or(args.positional.first().map(String::as_str)) {
+        Some("asciidoc") => Box::new(AsciiDocFormatter { date_format }),
+        _ => Box::new(MarkdownGitHubFormatter { date_format }),
+    }
+}
+
+fn render(mut deserialized: Document, args: &Args) {
+    let formatter = output_formatter(args);
+
+    if args.flag("omit-unreleased") {
+        deserialized.releases.retain(|name, _| !is_unreleased(name));
+    }
+
+    if args.flag("sort") {
+        if let Err(err) = sort_releases_by_version(&mut deserialized) {
+            fail(err);
+        }
+    }
+
+    exit_on_errors(&validate(&deserialized));
+
+    let mut commit_abbreviation = args.option("abbrev").map(|abbrev| {
+        let length = abbrev
+            .parse::<usize>()
+            .unwrap_or_else(|_| fail(format!("--abbrev must be a number, not '{}'", abbrev)));
+        if length < MIN_COMMIT_ABBREVIATION {
+            fail(format!("--abbrev must be at least {}, not {}", MIN_COMMIT_ABBREVIATION, length));
+        }
+        length
+    });
+
+    let mut commits = VerifiedCommits::default();
+    if let Some(repository) = open_git_repository(args) {
+        let (verified, errors) = verify_commits(&deserialized, &repository).unwrap_or_else(|err| fail(err));
+        exit_on_errors(&errors);
+        commits = verified;
+        commit_abbreviation = commit_abbreviation.or(Some(DEFAULT_COMMIT_ABBREVIATION));
+    }
+
+    let options = RenderOptions {
+        compare_links: args.flag("compare-links"),
+        commits,
+        commit_abbreviation,
+        releases: version_range(args, &deserialized),
+        heading: !args.flag("no-heading"),
+    };
+
+    print_document(deserialized, &options, formatter.as_ref());
+}
+
+// Formats of the rendered changelog and the documents that are printed like it.
+const DOCUMENT_FORMATS: &[&str] = &["markdown", "asciidoc"];
+
+// The commands, with the number of arguments they take after the command name and the values `--format` accepts.
+// `asciidoc` and `markdown` select the format of the rendered changelog.
+const COMMANDS: [(&str, usize, &[&str]); 15] = [
+    ("diff", 2, &["markdown", "asciidoc", "json"]),
+    ("check", 0, &[]),
+    ("fill-dates", 0, &[]),
+    ("fill-assets", 1, &[]),
+    ("check-history", 0, &[]),
+    ("query", 0, &["json"]),
+    ("whats-new", 2, DOCUMENT_FORMATS),
+    ("migration-guide", 0, DOCUMENT_FORMATS),
+    ("deprecations", 0, &[]),
+    ("workarounds", 0, &[]),
+    ("experimental", 0, &[]),
+    ("check-support", 0, &[]),
+    ("advisories", 0, &["osv", "rustsec"]),
+    ("asciidoc", 0, DOCUMENT_FORMATS),
+    ("markdown", 0, DOCUMENT_FORMATS),
+];
+
+fn main() {
+    let args = Args::parse(env::args().skip(1)).unwrap_or_else(|err| fail(err));
+
+    let command = args.positional.first().map(String::as_str);
+    let formats = match command {
+        Some(command) => {
+            let (_, max_arguments, formats) = COMMANDS
+                .iter()
+                .find(|(name, _, _)| *name == command)
+                .unwrap_or_else(|| fail(format!("unknown command '{}'", command)));
+            if let Some(unexpected) = args.positional.get(max_arguments + 1) {
+                fail(format!("unexpected argument '{}' for '{}'", unexpected, command));
+            }
+            *formats
+        }
+        None => DOCUMENT_FORMATS,
+    };
+    if let Some(format) = args.option("format") {
+        let command = command.unwrap_or("render");
+        match formats.is_empty() {
+            true => fail(format!("'{}' has no --format", command)),
+            false if !formats.contains(&format) => fail(format!(
+                "--format '{}' is not supported by '{}', use {}",
+                format,
+                command,
+                formats.join(", ")
+            )),
+            false => {}
         }
     }
+    if command == Some("diff") {
+        // Diff reads its two documents itself.
+        diff(&args);
+        return;
+    }
+
+    let text = read_document_text(&args);
+    let deserialized: Document = serde_yaml::from_str(&text).unwrap_or_else(|err| fail(err));
+
+    match command {
+        Some("check") => check(&deserialized, &args),
+        Some("fill-dates") => fill_dates(&text, &deserialized, &args),
+        Some("fill-assets") => fill_assets(&text, &deserialized, &args),
+        Some("check-history") => check_history(&deserialized, &args),
+        Some("query") => query(&deserialized, &args),
+        Some("whats-new") => whats_new_since(&deserialized, &args),
+        Some("migration-guide") => print_upgrade_guide(&deserialized, &args),
+        Some("deprecations") => deprecation_report(&deserialized),
+        Some("workarounds") => workaround_report(&deserialized, &args),
+        Some("experimental") => experimental_report(&deserialized),
+        Some("check-support") => support_report(&deserialized, &args),
+        Some("advisories") => advisory_report(&deserialized, &args),
+        _ => render(deserialized, &args),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::Forge;
+
+    const RELEASES: &str = r"
+repo: piot/nimble
+releases:
+  unreleased:
+    sections:
+      general:
+        changes:
+          added: [Replays]
+  1.3.0:
+    date: 2024-04-01
+    yanked: corrupts save files
+  1.2.0:
+    date: 2024-03-01
+  1.1.0:
+    date: 2024-02-01
+  1.0.0:
+    date: 2024-01-01
+";
+
+    fn parse_args(line: &str) -> Args {
+        Args::parse(line.split_whitespace().map(str::to_string)).unwrap()
+    }
+
+    fn selected(line: &str) -> Vec<String> {
+        let document: Document = serde_yaml::from_str(RELEASES).unwrap();
+        let range = version_range(&parse_args(line), &document);
+        document.releases.keys().filter(|name| range.contains(name)).cloned().collect()
+    }
+
+    #[test]
+    fn inclusive_and_exclusive_bounds() {
+        assert_eq!(selected("--since 1.1.0 --until 1.2.0"), vec!["1.2.0", "1.1.0"]);
+        assert_eq!(selected("--after 1.1.0 --before 1.2.0"), Vec::<String>::new());
+        assert_eq!(selected("--after 1.0.0 --until 1.2.0"), vec!["1.2.0", "1.1.0"]);
+        assert_eq!(selected("--since 1.1.0 --before 1.2.0"), vec!["1.1.0"]);
+        // Without an upper bound the unreleased changes are in the range.
+        assert_eq!(selected("--since 1.2.0"), vec!["unreleased", "1.2.0"]);
+        assert_eq!(selected("--version 1.1.0"), vec!["1.1.0"]);
+    }
+
+    fn links<F: LinkFormatter + LinkDefinitionFormatter>(
+        version_name: &str,
+        tag: Option<&str>,
+        previous_tag: Option<&str>,
+        compare_links: bool,
+        formatter: &F,
+    ) -> (String, Option<String>, Option<String>) {
+        let urls = Forge::default().url_templates();
+        let links = release_links(version_name, tag, previous_tag, "piot/nimble", &urls, compare_links, formatter);
+        (links.heading, links.full_diff, links.definition)
+    }
+
+    #[test]
+    fn yanked_releases_need_to_be_asked_for() {
+        assert_eq!(selected("--latest"), vec!["1.2.0"]);
+        assert_eq!(selected("--latest --include-yanked"), vec!["1.3.0"]);
+        assert_eq!(selected("--version 1.3.0"), vec!["1.3.0"]);
+        assert_eq!(selected("--since 1.2.0 --until 1.3.0"), vec!["1.2.0"]);
+        assert_eq!(selected("--since 1.2.0 --until 1.3.0 --include-yanked"), vec!["1.3.0", "1.2.0"]);
+    }
+
+    #[test]
+    fn whats_new_target_skips_yanked_releases() {
+        let document: Document = serde_yaml::from_str(RELEASES).unwrap();
+        let target = |line: &str| whats_new_range(&document, &parse_args(line)).1.to_string();
+        assert_eq!(target("whats-new 1.0.0"), "1.2.0");
+        assert_eq!(target("whats-new 1.0.0 --include-yanked"), "1.3.0");
+        assert_eq!(target("whats-new 1.0.0 1.3.0"), "1.3.0");
+    }
+
+    #[test]
+    fn breaking_summary_of_all_scopes() {
+        let document: Document = serde_yaml::from_str(
+            r"
+repo: piot/nimble
+repos:
+  clog:
+    repo: piot/clog
+    name: Clog
+    description: Logging
+releases:
+  1.0.0:
+    date: 2024-01-01
+    sections:
+      general:
+        changes:
+          added: [Lobby]
+          changed:
+            - text: Renamed connect to open
+              breaking: true
+          breaking: [Dropped the sync api]
+    repos:
+      clog:
+        removed:
+          - text: 'Removed the log macro, see #4'
+            breaking: true
+",
+        )
+        .unwrap();
+        let formatter = MarkdownGitHubFormatter {
+            date_format: DateFormat::default(),
+        };
+        let links = DocumentLinks::without_commits(&document);
+        assert_eq!(
+            breaking_summary(&document.releases["1.0.0"], &links, &formatter),
+            vec![
+                "Dropped the sync api",
+                "Renamed connect to open",
+                "Removed the log macro, see [#4](https://github.com/piot/clog/pull/4)",
+            ]
+        );
+    }
+
+    #[test]
+    fn markdown_compare_links_and_definitions() {
+        let markdown = MarkdownGitHubFormatter {
+            date_format: DateFormat::default(),
+        };
+        assert_eq!(
+            links("v1.1.0", Some("v1.1.0"), Some("v1.0.0"), true, &markdown),
+            (
+                "[v1.1.0]".to_string(),
+                Some("[Full diff](https://github.com/piot/nimble/compare/v1.0.0...v1.1.0)".to_string()),
+                Some("[v1.1.0]: https://github.com/piot/nimble/releases/tag/v1.1.0".to_string()),
+            )
+        );
+        // The first release has nothing to compare with.
+        assert_eq!(
+            links("v1.0.0", Some("v1.0.0"), None, true, &markdown),
+            (
+                "[v1.0.0]".to_string(),
+                None,
+                Some("[v1.0.0]: https://github.com/piot/nimble/releases/tag/v1.0.0".to_string()),
+            )
+        );
+        // The unreleased heading already links to the changes since the latest tag.
+        assert_eq!(
+            links("Unreleased", None, Some("v1.1.0"), true, &markdown),
+            (
+                "[Unreleased]".to_string(),
+                None,
+                Some("[Unreleased]: https://github.com/piot/nimble/compare/v1.1.0...HEAD".to_string()),
+            )
+        );
+        assert_eq!(
+            links("v1.1.0", Some("v1.1.0"), Some("v1.0.0"), false, &markdown),
+            ("[v1.1.0](https://github.com/piot/nimble/releases/tag/v1.1.0)".to_string(), None, None)
+        );
+    }
+
+    #[test]
+    fn asciidoc_compare_links_are_inline() {
+        let asciidoc = AsciiDocFormatter {
+            date_format: DateFormat::default(),
+        };
+        assert_eq!(
+            links("v1.1.0", Some("v1.1.0"), Some("v1.0.0"), true, &asciidoc),
+            (
+                "link:https://github.com/piot/nimble/releases/tag/v1.1.0[v1.1.0]".to_string(),
+                Some("link:https://github.com/piot/nimble/compare/v1.0.0...v1.1.0[Full diff]".to_string()),
+                None,
+            )
+        );
+    }
 }
@@ -0,0 +1,113 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use chrono::NaiveDate;
+
+use crate::autolink::DocumentLinks;
+use crate::formatter::{DateFormatter, EmojiFormatter, HeadingFormatter, LinkFormatter};
+use crate::version::{is_unreleased, VersionRange};
+use crate::yaml::{Document, Entry, Scope};
+use crate::{description_from_category, replace_line, CategoryType};
+
+pub struct MigrationStep<'a> {
+    pub category: CategoryType,
+    pub entry: &'a Entry,
+    // Repo is the `repos` key the step is listed under, which its references link to.
+    pub repo: Option<&'a str>,
+}
+
+pub struct MigrationRelease<'a> {
+    pub version: &'a str,
+    pub date: Option<NaiveDate>,
+    pub steps: Vec<MigrationStep<'a>>,
+}
+
+/// The breaking and deprecated entries of the releases in the range, oldest release first,
+/// which is the order an upgrading user has to apply them in.
+/// The steps of a `yanked` release are listed under the next release in the range, which is the one a user upgrades to.
+pub fn migration_guide<'a>(
+    document: &'a Document,
+    range: &VersionRange,
+    yanked: &[String],
+) -> Vec<MigrationRelease<'a>> {
+    let mut releases = Vec::new();
+    let mut carried: Option<MigrationRelease> = None;
+    for (version, release) in document.releases.iter().rev() {
+        if !range.contains(version) {
+            continue;
+        }
+
+        let mut steps = carried.take().map(|yanked_release| yanked_release.steps).unwrap_or_default();
+        for (scope, changes) in release.scoped_changes() {
+            let repo = match scope {
+                Scope::Repo(key) => Some(key),
+                _ => None,
+            };
+            for (category, entry) in changes.entries() {
+                if entry.can_have_migration(category) {
+                    steps.push(MigrationStep { category, entry, repo });
+                }
+            }
+        }
+
+        let migration_release = MigrationRelease {
+            version,
+            date: release.date,
+            steps,
+        };
+        if yanked.contains(version) {
+            carried = Some(migration_release);
+        } else if !migration_release.steps.is_empty() {
+            releases.push(migration_release);
+        }
+    }
+    // A yanked release without a later release in the range keeps its own steps.
+    releases.extend(carried.filter(|yanked_release| !yanked_release.steps.is_empty()));
+    releases
+}
+
+/// Prints an upgrade document with a heading for each step, followed by its migration note.
+pub fn print_migration_guide<F: LinkFormatter + HeadingFormatter + EmojiFormatter + DateFormatter + ?Sized>(
+    releases: &[MigrationRelease],
+    document: &Document,
+    heading: bool,
+    formatter: &F,
+) {
+    let document_links = DocumentLinks::without_commits(document);
+
+    if heading {
+        println!("{}", formatter.heading(1, "Upgrade guide"));
+    }
+
+    for release in releases {
+        let version_name = match is_unreleased(release.version) {
+            true => "Unreleased",
+            false => release.version,
+        };
+        let mut release_heading = format!("{} {}", formatter.emoji_tag(), version_name);
+        if let Some(date) = &release.date {
+            release_heading += &format!(" ({})", formatter.date(date));
+        }
+        println!("\n{}", formatter.heading(2, &release_heading));
+
+        for step in &release.steps {
+            let links = step
+                .repo
+                .and_then(|key| document_links.repo(key))
+                .unwrap_or_else(|| document_links.document());
+            let step_heading = format!(
+                "{}[{}] {}",
+                formatter.emoji(&step.category),
+                description_from_category(&step.category),
+                replace_line(step.entry.text.trim(), &links, formatter)
+            );
+            println!("\n{}", formatter.heading(3, &step_heading));
+
+            // The note is printed as it is, so code blocks are not touched by the link replacement.
+            if let Some(migration) = &step.entry.migration {
+                println!("\n{}", migration.trim());
+            }
+        }
+    }
+}
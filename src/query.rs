@@ -0,0 +1,94 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use regex::Regex;
+use serde::Serialize;
+
+use crate::autolink::{mentions, pull_request_references};
+use crate::date::ISO_DATE_FORMAT;
+use crate::version::VersionRange;
+use crate::yaml::{Document, Scope};
+use crate::CategoryType;
+
+/// Filters for the entries of a document. An entry matches if it passes every filter that is set.
+pub struct Query {
+    pub pattern: Option<Regex>,
+    pub category: Option<CategoryType>,
+    pub range: VersionRange,
+    pub section: Option<String>,
+    pub package: Option<String>,
+    pub repo: Option<String>,
+    // Author is a user mentioned as `@user` in the entry.
+    pub author: Option<String>,
+    // Pull request is a number referenced as `#12`, with or without a qualifier.
+    pub pull_request: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueryMatch {
+    pub version: String,
+    pub date: Option<String>,
+    // Scope is `section`, `package` or `repo`.
+    pub scope: &'static str,
+    pub name: String,
+    pub category: CategoryType,
+    pub entry: String,
+}
+
+impl Query {
+    fn matches_scope(&self, scope: Scope) -> bool {
+        let wanted = match scope {
+            Scope::Section(_) => &self.section,
+            Scope::Package(_) => &self.package,
+            Scope::Repo(_) => &self.repo,
+        };
+        let any_scope_filter = self.section.is_some() || self.package.is_some() || self.repo.is_some();
+        match wanted {
+            Some(name) => name == scope.name(),
+            None => !any_scope_filter,
+        }
+    }
+
+    fn matches_entry(&self, category: CategoryType, entry: &str) -> bool {
+        self.category.is_none_or(|wanted| wanted == category)
+            && self.pattern.as_ref().is_none_or(|pattern| pattern.is_match(entry))
+            && self
+                .author
+                .as_ref()
+                .is_none_or(|author| mentions(entry).contains(&author.as_str()))
+            && self.pull_request.is_none_or(|number| {
+                pull_request_references(entry)
+                    .iter()
+                    .any(|(_, referenced)| *referenced == number)
+            })
+    }
+
+    /// All matching entries, in the order they are listed in the document.
+    pub fn run(&self, document: &Document) -> Vec<QueryMatch> {
+        let mut matches = Vec::new();
+        for (version, release) in &document.releases {
+            if !self.range.contains(version) {
+                continue;
+            }
+            for (scope, changes) in release.scoped_changes() {
+                if !self.matches_scope(scope) {
+                    continue;
+                }
+                for (category, entry) in changes.entries() {
+                    if self.matches_entry(category, &entry.text) {
+                        matches.push(QueryMatch {
+                            version: version.clone(),
+                            date: release.date.map(|date| date.format(ISO_DATE_FORMAT).to_string()),
+                            scope: scope.kind(),
+                            name: scope.name().to_string(),
+                            category,
+                            entry: entry.text.trim().to_string(),
+                        });
+                    }
+                }
+            }
+        }
+        matches
+    }
+}
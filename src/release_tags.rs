@@ -0,0 +1,178 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use chrono::NaiveDate;
+
+use crate::date::ISO_DATE_FORMAT;
+use crate::git::Tag;
+use crate::links::{tag_name, version_from_tag};
+use crate::validate::ValidationError;
+use crate::version::{is_unreleased, parse_version};
+use crate::yaml::Document;
+
+pub struct TagReport {
+    // Problems are releases without a tag, tags without a release and dates that differ from the tag.
+    pub problems: Vec<ValidationError>,
+    // Missing dates are the tag dates of the tagged releases that have no date.
+    pub missing_dates: Vec<(String, NaiveDate)>,
+}
+
+/// Compares the releases with the tags in the repository, using the tag name template of the document.
+/// Releases without a date are not released yet, so it is fine that they are not tagged.
+pub fn check_release_tags(document: &Document, tags: &[Tag]) -> TagReport {
+    let scheme = document.versioning.unwrap_or_default();
+    let tag_template = document.tag.as_deref();
+    let mut report = TagReport {
+        problems: Vec::new(),
+        missing_dates: Vec::new(),
+    };
+    let mut release_tags = Vec::new();
+
+    for (name, release) in &document.releases {
+        if is_unreleased(name) {
+            continue;
+        }
+        let expected_tag = tag_name(tag_template, name);
+        let tag = tags.iter().find(|tag| tag.name == expected_tag);
+        release_tags.push(expected_tag.clone());
+
+        match (tag, release.date) {
+            (None, Some(_)) => report.problems.push(ValidationError {
+                location: name.clone(),
+                message: format!("tag '{}' is not found", expected_tag),
+            }),
+            (Some(tag), Some(date)) if tag.date != date => report.problems.push(ValidationError {
+                location: name.clone(),
+                message: format!("date {} differs from {} of tag '{}'", date, tag.date, tag.name),
+            }),
+            (Some(tag), None) => report.missing_dates.push((name.clone(), tag.date)),
+            _ => {}
+        }
+    }
+
+    for tag in tags {
+        let is_release_tag = version_from_tag(tag_template, &tag.name)
+            .is_some_and(|version| parse_version(version, scheme).is_ok());
+        if is_release_tag && !release_tags.contains(&tag.name) {
+            report.problems.push(ValidationError {
+                location: tag.name.clone(),
+                message: "tag has no release".to_string(),
+            });
+        }
+    }
+
+    report
+}
+
+pub(crate) fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+pub(crate) fn unquoted_key(line: &str) -> Option<&str> {
+    let (key, _) = line.trim().split_once(':')?;
+    Some(key.trim().trim_matches(|c| c == '\'' || c == '"'))
+}
+
+/// The line of the release key in the yaml lines, and its indentation.
+pub(crate) fn release_key_line(lines: &[String], release_name: &str) -> Result<(usize, usize), String> {
+    let releases_line = lines
+        .iter()
+        .position(|line| indentation(line) == 0 && unquoted_key(line) == Some("releases"))
+        .ok_or("'releases' is not found")?;
+
+    let mut release_indentation = None;
+    for (index, line) in lines.iter().enumerate().skip(releases_line + 1) {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let line_indentation = indentation(line);
+        if line_indentation == 0 {
+            break;
+        }
+        if *release_indentation.get_or_insert(line_indentation) == line_indentation
+            && unquoted_key(line) == Some(release_name)
+        {
+            return Ok((index, line_indentation));
+        }
+    }
+    Err(format!("release '{}' is not found", release_name))
+}
+
+/// Adds a `date` to each release in the yaml text. The rest of the text, including comments, is kept as it is.
+pub fn fill_release_dates(yaml: &str, dates: &[(String, NaiveDate)]) -> Result<String, String> {
+    let mut lines: Vec<String> = yaml.lines().map(str::to_string).collect();
+
+    for (release_name, date) in dates {
+        let (key_line, release_indentation) = release_key_line(&lines, release_name)?;
+
+        // A comment after the key is kept.
+        let (key, value) = lines[key_line].split_once(':').unwrap_or_default();
+        let (value, comment) = match value.find(" #") {
+            Some(start) => value.split_at(start),
+            None => (value, ""),
+        };
+        match value.trim() {
+            "" => {}
+            "{}" => lines[key_line] = format!("{}:{}", key, comment),
+            _ => return Err(format!("release '{}' is written inline and can not be filled", release_name)),
+        }
+
+        let child_indentation = lines
+            .iter()
+            .skip(key_line + 1)
+            .find(|line| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|line| indentation(line))
+            .filter(|child_indentation| *child_indentation > release_indentation)
+            .unwrap_or(release_indentation + 2);
+
+        lines.insert(
+            key_line + 1,
+            format!("{}date: '{}'", " ".repeat(child_indentation), date.format(ISO_DATE_FORMAT)),
+        );
+    }
+
+    let mut result = lines.join("\n");
+    if yaml.ends_with('\n') {
+        result.push('\n');
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(yaml: &str, release_name: &str) -> Result<String, String> {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        fill_release_dates(yaml, &[(release_name.to_string(), date)])
+    }
+
+    #[test]
+    fn quoted_key() {
+        let yaml = "repo: piot/nimble\nreleases:\n  '1.0.0':\n    notice: first\n";
+        assert_eq!(
+            fill(yaml, "1.0.0").unwrap(),
+            "repo: piot/nimble\nreleases:\n  '1.0.0':\n    date: '2024-03-01'\n    notice: first\n"
+        );
+    }
+
+    #[test]
+    fn comments_are_kept() {
+        let yaml = "releases:\n  v1.0.0: # first release\n# a comment\n      notice: first";
+        assert_eq!(
+            fill(yaml, "v1.0.0").unwrap(),
+            "releases:\n  v1.0.0: # first release\n      date: '2024-03-01'\n# a comment\n      notice: first"
+        );
+        assert_eq!(
+            fill("releases:\n  v1.0.0: {} # empty\n", "v1.0.0").unwrap(),
+            "releases:\n  v1.0.0: # empty\n    date: '2024-03-01'\n"
+        );
+    }
+
+    #[test]
+    fn inline_and_missing_releases_fail() {
+        assert!(fill("releases:\n  v1.0.0: { notice: first }\n", "v1.0.0").is_err());
+        assert!(fill("releases:\n  v1.0.0:\n    notice: first\n", "v2.0.0").is_err());
+    }
+}
@@ -0,0 +1,165 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::date::deserialize_optional_date;
+use crate::validate::ValidationError;
+use crate::version::{is_unreleased, parse_release_line, parse_version};
+use crate::yaml::{is_yanked, Document};
+
+/// How long a line of releases, e.g. `2` or `1.4`, is supported.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SupportLine {
+    // Lts marks a long-term support line.
+    #[serde(default)]
+    pub lts: bool,
+    // Active until is the last day of bug fixes. After it the line only gets security fixes until the end of life.
+    #[serde(default, deserialize_with = "deserialize_optional_date")]
+    pub active_until: Option<NaiveDate>,
+    // Eol is the end of life, the last day the line is supported.
+    #[serde(default, deserialize_with = "deserialize_optional_date")]
+    pub eol: Option<NaiveDate>,
+    // Supported is set to false once the line is no longer supported. Defaults to true.
+    pub supported: Option<bool>,
+}
+
+impl SupportLine {
+    pub fn is_supported(&self) -> bool {
+        self.supported.unwrap_or(true)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SupportStatus {
+    Supported,
+    Lts,
+    SecurityFixes,
+    EndOfLife,
+}
+
+impl SupportStatus {
+    pub fn description(&self) -> &'static str {
+        match self {
+            SupportStatus::Supported => "supported",
+            SupportStatus::Lts => "LTS",
+            SupportStatus::SecurityFixes => "security fixes only",
+            SupportStatus::EndOfLife => "end of life",
+        }
+    }
+}
+
+pub struct LineSupport<'a> {
+    pub key: &'a str,
+    pub line: &'a SupportLine,
+    // Latest is the newest release in the line that is not yanked.
+    pub latest: Option<&'a str>,
+    pub status: SupportStatus,
+}
+
+/// The status of each release line on `today`, in the order the lines are listed in `support`.
+/// Without `today` the status only follows `supported` and `lts`, so the same document always gives the same status.
+pub fn support_lines(document: &Document, today: Option<NaiveDate>) -> Vec<LineSupport<'_>> {
+    let scheme = document.versioning.unwrap_or_default();
+    let mut result = Vec::new();
+    for (key, line) in document.support.iter().flatten() {
+        let Ok(release_line) = parse_release_line(key) else {
+            continue;
+        };
+        let latest = document
+            .releases
+            .iter()
+            .filter(|(name, release)| !is_unreleased(name) && !is_yanked(&release.yanked))
+            .find(|(name, _)| parse_version(name, scheme).is_ok_and(|version| release_line.contains(&version)))
+            .map(|(name, _)| name.as_str());
+
+        let is_past = |date: Option<NaiveDate>| today.zip(date).is_some_and(|(today, date)| date < today);
+        let status = if !line.is_supported() || is_past(line.eol) {
+            SupportStatus::EndOfLife
+        } else if is_past(line.active_until) {
+            SupportStatus::SecurityFixes
+        } else if line.lts {
+            SupportStatus::Lts
+        } else {
+            SupportStatus::Supported
+        };
+
+        result.push(LineSupport {
+            key,
+            line,
+            latest,
+            status,
+        });
+    }
+    result
+}
+
+/// The lines that are still marked as supported although their end of life has passed.
+pub fn expired_lines(document: &Document, today: NaiveDate) -> Vec<ValidationError> {
+    let mut expired = Vec::new();
+    for (key, line) in document.support.iter().flatten() {
+        if let (true, Some(eol)) = (line.is_supported(), line.eol) {
+            if eol < today {
+                expired.push(ValidationError {
+                    location: format!("support {}", key),
+                    message: format!("end of life was {}, but the line is still supported, set `supported: false`", eol),
+                });
+            }
+        }
+    }
+    expired
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = r"
+repo: piot/nimble
+support:
+  '2':
+    lts: true
+    active_until: 2027-06-30
+    eol: 2028-12-31
+  '1':
+    eol: 2026-12-31
+  '0':
+    supported: false
+releases:
+  2.1.0:
+    date: 2026-03-01
+  1.4.0:
+    date: 2025-03-01
+";
+
+    #[test]
+    fn status_without_today() {
+        let document: Document = serde_yaml::from_str(DOCUMENT).unwrap();
+        let lines: Vec<_> = support_lines(&document, None)
+            .iter()
+            .map(|line| (line.status, line.latest))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (SupportStatus::Lts, Some("2.1.0")),
+                (SupportStatus::Supported, Some("1.4.0")),
+                (SupportStatus::EndOfLife, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn status_on_today() {
+        let document: Document = serde_yaml::from_str(DOCUMENT).unwrap();
+        let today = NaiveDate::from_ymd_opt(2027, 7, 1);
+        let statuses: Vec<_> = support_lines(&document, today).iter().map(|line| line.status).collect();
+        assert_eq!(
+            statuses,
+            vec![SupportStatus::SecurityFixes, SupportStatus::EndOfLife, SupportStatus::EndOfLife]
+        );
+    }
+}
@@ -0,0 +1,253 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::assets::is_sha256;
+use crate::autolink::unknown_qualifiers;
+use crate::version::{is_unreleased, parse_release_line, parse_version, ReleaseVersion};
+use crate::yaml::{is_yanked, Document, Scope};
+use crate::CategoryType;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    // Location is the release, or the part of the document, that the error is about.
+    pub location: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+fn error(location: &str, message: String) -> ValidationError {
+    ValidationError {
+        location: location.to_string(),
+        message,
+    }
+}
+
+/// Checks that every release key is a valid version and that the releases are listed
+/// newest first, with no duplicated versions and no dates going forward in time.
+/// Releases without a date are unreleased and must be listed before all dated releases.
+/// The `unreleased` block must be the first release and can not have a date.
+/// Only released versions and the packages of a release can be yanked.
+/// Only workaround and noted entries can have an id, ids must be unique, and `resolves` must be one of the ids.
+/// Only security entries can have an advisory, with known advisory ids that are unique, a CVSS vector and valid
+/// version requirements.
+/// Only breaking and deprecated entries can have a migration note, and a `breaking` entry can not be marked as not breaking.
+/// Support lines must be version prefixes like `2` or `1.4`, and can not be active after their end of life.
+/// Asset file names must be unique within a release, and checksums must be SHA-256 hex digests.
+/// The repos listed in a release must be keys in `repos`.
+/// All autolinks must compile to valid regular expressions, and a qualifier like `clgo` in `clgo#12` that is close
+/// to a key in `repos` is reported as misspelled.
+pub fn validate(document: &Document) -> Vec<ValidationError> {
+    let scheme = document.versioning.unwrap_or_default();
+    let mut errors = Vec::new();
+    let repo_keys: Vec<&str> = document.repos.iter().flatten().map(|(key, _)| key.as_str()).collect();
+
+    for autolink in document.autolinks.iter().flatten() {
+        if let Err(err) = autolink.compile() {
+            errors.push(error("autolinks", err));
+        }
+    }
+
+    for (key, line) in document.support.iter().flatten() {
+        let location = format!("support {}", key);
+        if let Err(err) = parse_release_line(key) {
+            errors.push(error(&location, err));
+        }
+        if let (Some(active_until), Some(eol)) = (line.active_until, line.eol) {
+            if active_until > eol {
+                errors.push(error(&location, format!("active until {} is after the end of life {}", active_until, eol)));
+            }
+        }
+    }
+
+    let mut ids: Vec<&str> = Vec::new();
+    for (name, release) in &document.releases {
+        for (scope, changes) in release.scoped_changes() {
+            for (category, entry) in changes.entries() {
+                let Some(id) = &entry.id else {
+                    continue;
+                };
+                let location = format!("{} ({})", name, scope);
+                if !matches!(category, CategoryType::Workaround | CategoryType::Noted) {
+                    errors.push(error(&location, format!("'{}' can not have an id, only workaround and noted entries can", entry.text.trim())));
+                } else if ids.contains(&id.as_str()) {
+                    errors.push(error(&location, format!("id '{}' is used by more than one entry", id)));
+                } else {
+                    ids.push(id);
+                }
+            }
+        }
+    }
+
+    let mut advisory_ids: Vec<&str> = Vec::new();
+    let mut seen: Vec<(ReleaseVersion, &str)> = Vec::new();
+    let mut previous: Option<(&str, ReleaseVersion)> = None;
+    let mut previous_dated: Option<(&str, NaiveDate)> = None;
+
+    for (name, release) in &document.releases {
+        for (scope, changes) in release.scoped_changes() {
+            if changes.yanked.is_some() && !matches!(scope, Scope::Package(_)) {
+                errors.push(error(
+                    &format!("{} ({})", name, scope),
+                    "only packages can be yanked, use `yanked` on the release instead".to_string(),
+                ));
+            }
+            if let Scope::Repo(key) = scope {
+                if !repo_keys.contains(&key) {
+                    errors.push(error(&format!("{} ({})", name, scope), format!("'{}' is not a key in repos", key)));
+                }
+            }
+            for (category, entry) in changes.entries() {
+                if entry.migration.is_some() && !entry.can_have_migration(category) {
+                    errors.push(error(
+                        &format!("{} ({})", name, scope),
+                        format!("'{}' can not have a migration note, only breaking and deprecated entries can", entry.text.trim()),
+                    ));
+                }
+                if let Some(resolved) = &entry.resolves {
+                    if !matches!(category, CategoryType::Fixed | CategoryType::Improved) {
+                        errors.push(error(
+                            &format!("{} ({})", name, scope),
+                            format!("'{}' can not resolve anything, only fixed and improved entries can", entry.text.trim()),
+                        ));
+                    } else if !ids.contains(&resolved.as_str()) {
+                        errors.push(error(
+                            &format!("{} ({})", name, scope),
+                            format!("resolves '{}', which is not the id of a workaround or a known issue", resolved),
+                        ));
+                    }
+                }
+                if let Some(advisory) = &entry.advisory {
+                    if category != CategoryType::Security {
+                        errors.push(error(
+                            &format!("{} ({})", name, scope),
+                            format!("'{}' can not have an advisory, only security entries can", entry.text.trim()),
+                        ));
+                    }
+                    for problem in advisory.problems(name, scheme) {
+                        errors.push(error(&format!("{} ({})", name, scope), problem));
+                    }
+                    for id in &advisory.ids {
+                        if advisory_ids.contains(&id.as_str()) {
+                            errors.push(error(
+                                &format!("{} ({})", name, scope),
+                                format!("advisory id '{}' is used by more than one entry", id),
+                            ));
+                        } else {
+                            advisory_ids.push(id);
+                        }
+                    }
+                }
+                if category == CategoryType::Breaking && entry.breaking == Some(false) {
+                    errors.push(error(
+                        &format!("{} ({})", name, scope),
+                        format!("'{}' is listed as breaking, but marked as not breaking", entry.text.trim()),
+                    ));
+                }
+                for (qualifier, key) in unknown_qualifiers(&entry.text, &repo_keys) {
+                    let hint = key.map(|key| format!(", did you mean '{}'?", key)).unwrap_or_default();
+                    errors.push(error(
+                        &format!("{} ({})", name, scope),
+                        format!("'{}' in '{}' is not a key in repos{}", qualifier, entry.text.trim(), hint),
+                    ));
+                }
+            }
+        }
+    }
+
+    for (name, release) in &document.releases {
+        let mut file_names = Vec::new();
+        for asset in release.assets.iter().flatten() {
+            let file_name = asset.file_name(name);
+            if file_names.contains(&file_name) {
+                errors.push(error(name, format!("asset '{}' is listed more than once", file_name)));
+            }
+            if asset.sha256.as_deref().is_some_and(|sha256| !is_sha256(sha256)) {
+                errors.push(error(name, format!("sha256 of asset '{}' is not 64 hex digits", file_name)));
+            }
+            file_names.push(file_name);
+        }
+    }
+
+    for (index, (name, release)) in document.releases.iter().enumerate() {
+        if is_unreleased(name) {
+            if index != 0 {
+                errors.push(error(name, "must be the first release".to_string()));
+            }
+            if release.date.is_some() {
+                errors.push(error(name, "can not have a date".to_string()));
+            }
+            if is_yanked(&release.yanked) {
+                errors.push(error(name, "can not be yanked".to_string()));
+            }
+            continue;
+        }
+
+        let version = match parse_version(name, scheme) {
+            Ok(version) => version,
+            Err(err) => {
+                errors.push(error(name, err.reason));
+                continue;
+            }
+        };
+
+        let same_release = |(seen_version, _): &&(ReleaseVersion, &str)| seen_version.cmp_precedence(&version).is_eq();
+        if let Some((_, duplicate_name)) = seen.iter().find(same_release) {
+            errors.push(error(
+                name,
+                format!("duplicate of release '{}'", duplicate_name),
+            ));
+        } else if let Some((previous_name, previous_version)) = &previous {
+            if &version > previous_version {
+                errors.push(error(
+                    name,
+                    format!(
+                        "must be listed after '{}', releases are ordered newest first",
+                        previous_name
+                    ),
+                ));
+            }
+        }
+
+        match (&release.date, &previous_dated) {
+            (Some(date), Some((previous_name, previous_date))) if date > previous_date => {
+                errors.push(error(
+                    name,
+                    format!(
+                        "date {} is later than {} of the newer release '{}'",
+                        date, previous_date, previous_name
+                    ),
+                ));
+            }
+            (None, Some((previous_name, _))) => {
+                errors.push(error(
+                    name,
+                    format!(
+                        "date is missing, but the newer release '{}' is dated. Only unreleased versions at the top may omit it",
+                        previous_name
+                    ),
+                ));
+            }
+            _ => {}
+        }
+
+        if let Some(date) = release.date {
+            previous_dated = Some((name, date));
+        }
+        seen.push((version.clone(), name));
+        previous = Some((name, version));
+    }
+
+    errors
+}
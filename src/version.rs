@@ -0,0 +1,272 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::Bound;
+
+use serde::Deserialize;
+
+use crate::yaml::Document;
+
+/// How the release keys in `releases` are interpreted.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionScheme {
+    /// `MAJOR.MINOR.PATCH[-PRE][+BUILD]`, see https://semver.org
+    #[default]
+    SemVer,
+    /// `YYYY.MM[.MICRO]`, see https://calver.org
+    CalVer,
+}
+
+/// Calendar version. Components are compared numerically from left to right.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CalVer {
+    pub components: Vec<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReleaseVersion {
+    SemVer(semver::Version),
+    CalVer(CalVer),
+}
+
+impl PartialOrd for ReleaseVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ReleaseVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (ReleaseVersion::SemVer(a), ReleaseVersion::SemVer(b)) => a.cmp(b),
+            (ReleaseVersion::CalVer(a), ReleaseVersion::CalVer(b)) => a.cmp(b),
+            (ReleaseVersion::SemVer(_), ReleaseVersion::CalVer(_)) => Ordering::Less,
+            (ReleaseVersion::CalVer(_), ReleaseVersion::SemVer(_)) => Ordering::Greater,
+        }
+    }
+}
+
+impl fmt::Display for ReleaseVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReleaseVersion::SemVer(version) => write!(f, "{}", version),
+            ReleaseVersion::CalVer(calver) => {
+                let parts: Vec<String> = calver.components.iter().map(u64::to_string).collect();
+                write!(f, "{}", parts.join("."))
+            }
+        }
+    }
+}
+
+impl ReleaseVersion {
+    /// The numeric parts of the version, e.g. `[1, 4, 2]` for `1.4.2-beta`.
+    pub fn components(&self) -> Vec<u64> {
+        match self {
+            ReleaseVersion::SemVer(version) => vec![version.major, version.minor, version.patch],
+            ReleaseVersion::CalVer(calver) => calver.components.clone(),
+        }
+    }
+
+    /// Compares the versions without the build metadata, so `1.0.0` and `1.0.0+build.5` are the same release.
+    pub fn cmp_precedence(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (ReleaseVersion::SemVer(a), ReleaseVersion::SemVer(b)) => a.cmp_precedence(b),
+            _ => self.cmp(other),
+        }
+    }
+}
+
+/// A line of releases, e.g. `2` or `1.4`, which is every version that starts with those parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseLine {
+    pub components: Vec<u64>,
+}
+
+impl ReleaseLine {
+    pub fn contains(&self, version: &ReleaseVersion) -> bool {
+        version.components().starts_with(&self.components)
+    }
+}
+
+/// Parses a release line, e.g. `v2`, `1.4` or `1.4.x`.
+pub fn parse_release_line(name: &str) -> Result<ReleaseLine, String> {
+    let trimmed = name.trim();
+    let without_prefix = trimmed.strip_prefix('v').unwrap_or(trimmed);
+    let without_wildcard = without_prefix.strip_suffix(".x").unwrap_or(without_prefix);
+
+    let mut components = Vec::new();
+    for part in without_wildcard.split('.') {
+        let value = part
+            .parse::<u64>()
+            .map_err(|_| format!("release line '{}' must be numbers separated by dots, e.g. `2` or `1.4`", name))?;
+        components.push(value);
+    }
+    Ok(ReleaseLine { components })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionError {
+    pub version: String,
+    pub reason: String,
+}
+
+impl fmt::Display for VersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "release '{}' is not a valid version: {}", self.version, self.reason)
+    }
+}
+
+impl std::error::Error for VersionError {}
+
+fn parse_calver(s: &str) -> Result<CalVer, String> {
+    let parts: Vec<&str> = s.split('.').collect();
+    if parts.len() < 2 || parts.len() > 3 {
+        return Err("expected YYYY.MM or YYYY.MM.MICRO".to_string());
+    }
+
+    let mut components = Vec::new();
+    for part in &parts {
+        let value = part
+            .parse::<u64>()
+            .map_err(|_| format!("'{}' is not a number", part))?;
+        components.push(value);
+    }
+
+    if parts[0].len() != 4 {
+        return Err(format!("year '{}' must have four digits", parts[0]));
+    }
+    if !(1..=12).contains(&components[1]) {
+        return Err(format!("month '{}' must be between 1 and 12", parts[1]));
+    }
+
+    Ok(CalVer { components })
+}
+
+/// Release key for the pending work that is not yet part of any version.
+pub const UNRELEASED: &str = "unreleased";
+
+pub fn is_unreleased(name: &str) -> bool {
+    name.trim().eq_ignore_ascii_case(UNRELEASED)
+}
+
+/// Parses a release key, e.g. `v0.0.1-a06` or `2024.04.1`. A leading `v` is ignored.
+pub fn parse_version(name: &str, scheme: VersionScheme) -> Result<ReleaseVersion, VersionError> {
+    let trimmed = name.trim();
+    let without_prefix = trimmed.strip_prefix('v').unwrap_or(trimmed);
+
+    let result = match scheme {
+        VersionScheme::SemVer => semver::Version::parse(without_prefix)
+            .map(ReleaseVersion::SemVer)
+            .map_err(|err| err.to_string()),
+        VersionScheme::CalVer => parse_calver(without_prefix).map(ReleaseVersion::CalVer),
+    };
+
+    result.map_err(|reason| VersionError {
+        version: name.to_string(),
+        reason,
+    })
+}
+
+/// Sorts the releases with the newest version first, regardless of the order in the file.
+/// The unreleased block is always kept at the top.
+pub fn sort_releases_by_version(document: &mut Document) -> Result<(), VersionError> {
+    let scheme = document.versioning.unwrap_or_default();
+    for name in document.releases.keys().filter(|name| !is_unreleased(name)) {
+        parse_version(name, scheme)?;
+    }
+
+    document.releases.sort_by(|a_name, _, b_name, _| {
+        match (is_unreleased(a_name), is_unreleased(b_name)) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (false, false) => {
+                let a = parse_version(a_name, scheme).expect("checked above");
+                let b = parse_version(b_name, scheme).expect("checked above");
+                b.cmp(&a)
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Selects releases by version. The unreleased block is newer than any version.
+#[derive(Debug, Clone)]
+pub struct VersionRange {
+    pub scheme: VersionScheme,
+    pub lower: Bound<ReleaseVersion>,
+    pub upper: Bound<ReleaseVersion>,
+    // Excluded are the releases that are never in the range, e.g. yanked releases.
+    pub excluded: Vec<String>,
+}
+
+impl VersionRange {
+    pub fn new(scheme: VersionScheme) -> Self {
+        VersionRange {
+            scheme,
+            lower: Bound::Unbounded,
+            upper: Bound::Unbounded,
+            excluded: Vec::new(),
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        if self.excluded.iter().any(|excluded| excluded == name) {
+            return false;
+        }
+        if is_unreleased(name) {
+            return matches!(self.upper, Bound::Unbounded);
+        }
+        let Ok(version) = parse_version(name, self.scheme) else {
+            return false;
+        };
+
+        // Build metadata is ignored, so `--until 1.0.0` includes `1.0.0+build.5`.
+        let above_lower = match &self.lower {
+            Bound::Included(lower) => version.cmp_precedence(lower).is_ge(),
+            Bound::Excluded(lower) => version.cmp_precedence(lower).is_gt(),
+            Bound::Unbounded => true,
+        };
+        let below_upper = match &self.upper {
+            Bound::Included(upper) => version.cmp_precedence(upper).is_le(),
+            Bound::Excluded(upper) => version.cmp_precedence(upper).is_lt(),
+            Bound::Unbounded => true,
+        };
+        above_lower && below_upper
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_metadata_has_no_precedence() {
+        let version = |name| parse_version(name, VersionScheme::SemVer).unwrap();
+        assert!(version("v1.0.0").cmp_precedence(&version("1.0.0+build.5")).is_eq());
+        assert!(version("1.0.0-rc.1").cmp_precedence(&version("1.0.0")).is_lt());
+    }
+
+    #[test]
+    fn range_bounds_ignore_build_metadata() {
+        let version = |name| parse_version(name, VersionScheme::SemVer).unwrap();
+        let mut range = VersionRange::new(VersionScheme::SemVer);
+        range.lower = Bound::Included(version("1.0.0"));
+        range.upper = Bound::Included(version("1.0.0"));
+        assert!(range.contains("v1.0.0+b7"));
+        assert!(!range.contains("1.0.1"));
+        assert!(!range.contains("1.0.0-rc.1"));
+
+        range.lower = Bound::Excluded(version("0.9.0"));
+        range.upper = Bound::Excluded(version("1.0.0"));
+        assert!(range.contains("1.0.0-rc.1"));
+        assert!(!range.contains("1.0.0+b7"));
+        assert!(!range.contains("0.9.0+b3"));
+        assert!(!range.contains("unreleased"));
+    }
+}
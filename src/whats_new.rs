@@ -0,0 +1,161 @@
+/*----------------------------------------------------------------------------------------------------------
+ *  Copyright (c) Peter Bjorklund. All rights reserved. https://github.com/piot/changelog-yaml-rs
+ *  Licensed under the MIT License. See LICENSE in the project root for license information.
+ *--------------------------------------------------------------------------------------------------------*/
+use indexmap::IndexMap;
+
+use crate::autolink::DocumentLinks;
+use crate::formatter::{EmojiFormatter, HeadingFormatter, LinkFormatter};
+use crate::version::VersionRange;
+use crate::yaml::{Changes, Document, Scope};
+use crate::{print_line, CategoryType};
+
+pub struct NewEntry {
+    pub text: String,
+    pub breaking: bool,
+    // Repo is the key in `repos` if the entry is listed under a repo.
+    pub repo: Option<String>,
+}
+
+/// All entries of a range of releases merged into one release.
+/// A category can be listed twice, once for its breaking entries and once for the rest.
+pub struct WhatsNew {
+    pub categories: Vec<(CategoryType, Vec<NewEntry>)>,
+}
+
+/// Merges the entries of all releases in the range, grouped by category.
+/// An entry that is repeated in several releases is only listed once.
+/// Breaking entries of any category come first and security fixes next, since an upgrading user must read them first.
+pub fn whats_new(document: &Document, range: &VersionRange) -> WhatsNew {
+    let mut by_category: IndexMap<CategoryType, Vec<NewEntry>> = IndexMap::new();
+    for (category, _) in Changes::default().categories() {
+        by_category.entry(category).or_default();
+    }
+
+    for (version, release) in &document.releases {
+        if !range.contains(version) {
+            continue;
+        }
+        for (scope, changes) in release.scoped_changes() {
+            let repo = match scope {
+                Scope::Repo(key) => Some(key.to_string()),
+                _ => None,
+            };
+            for (category, entry) in changes.entries() {
+                let text = entry.text.trim();
+                let entries = by_category.entry(category).or_default();
+                if !entries.iter().any(|existing| existing.text == text && existing.repo == repo) {
+                    entries.push(NewEntry {
+                        text: text.to_string(),
+                        breaking: entry.is_breaking(category),
+                        repo: repo.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    let mut categories = Vec::new();
+    let mut security = Vec::new();
+    let mut rest = Vec::new();
+    for (category, entries) in by_category {
+        let (breaking, other): (Vec<_>, Vec<_>) = entries.into_iter().partition(|entry| entry.breaking);
+        categories.push((category, breaking));
+        match category {
+            CategoryType::Security => security.push((category, other)),
+            _ => rest.push((category, other)),
+        }
+    }
+    categories.extend(security);
+    categories.extend(rest);
+
+    WhatsNew {
+        categories: categories.into_iter().filter(|(_, entries)| !entries.is_empty()).collect(),
+    }
+}
+
+/// Prints the merged entries as a single release.
+pub fn print_whats_new<F: LinkFormatter + HeadingFormatter + EmojiFormatter + ?Sized>(
+    new: &WhatsNew,
+    document: &Document,
+    title: &str,
+    formatter: &F,
+) {
+    let document_links = DocumentLinks::without_commits(document);
+
+    println!("{}\n", formatter.heading(2, &format!("{} {}", formatter.emoji_tag(), title)));
+
+    for (category, entries) in &new.categories {
+        for entry in entries {
+            let links = entry
+                .repo
+                .as_ref()
+                .and_then(|key| document_links.repo(key))
+                .unwrap_or_else(|| document_links.document());
+            print_line(&links, category, &entry.text, "", entry.breaking, formatter);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::parse_version;
+    use std::ops::Bound;
+
+    const DOCUMENT: &str = r"
+repo: piot/nimble
+releases:
+  1.2.0:
+    date: 2024-03-01
+    sections:
+      general:
+        changes:
+          added: [Replays]
+          fixed: [Crash on connect]
+          security: [Token leak]
+  1.1.0:
+    date: 2024-02-01
+    sections:
+      general:
+        changes:
+          fixed: [Crash on connect]
+          changed:
+            - text: Renamed connect to open
+              breaking: true
+  1.0.0:
+    date: 2024-01-01
+    sections:
+      general:
+        changes:
+          added: [Lobby]
+";
+
+    #[test]
+    fn breaking_entries_come_first() {
+        let document: Document = serde_yaml::from_str(DOCUMENT).unwrap();
+        let mut range = VersionRange::new(document.versioning.unwrap_or_default());
+        range.lower = Bound::Excluded(parse_version("1.0.0", range.scheme).unwrap());
+        range.upper = Bound::Included(parse_version("1.2.0", range.scheme).unwrap());
+
+        let new = whats_new(&document, &range);
+        let categories: Vec<_> = new
+            .categories
+            .iter()
+            .map(|(category, entries)| {
+                let texts: Vec<_> = entries.iter().map(|entry| entry.text.as_str()).collect();
+                (*category, texts)
+            })
+            .collect();
+        assert_eq!(
+            categories,
+            vec![
+                (CategoryType::Changed, vec!["Renamed connect to open"]),
+                (CategoryType::Security, vec!["Token leak"]),
+                (CategoryType::Added, vec!["Replays"]),
+                (CategoryType::Fixed, vec!["Crash on connect"]),
+            ]
+        );
+        assert!(new.categories[0].1[0].breaking);
+    }
+}
@@ -3,67 +3,227 @@
  *  Licensed under the MIT License. See LICENSE in the project root for license information.
  *--------------------------------------------------------------------------------------------------------*/
 use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use chrono::NaiveDate;
 use indexmap::IndexMap;
-use serde::Deserialize;
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{Error, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
 
-#[derive(Debug, PartialEq, Deserialize)]
+use crate::CategoryType;
+use crate::advisory::Advisory;
+use crate::assets::Asset;
+use crate::autolink::Autolink;
+use crate::date::deserialize_optional_date;
+use crate::forge::Forge;
+use crate::links::UrlTemplateOverrides;
+use crate::support::SupportLine;
+use crate::version::{is_unreleased, VersionScheme};
+
+/// A change, written either as just the text or as a map with the text and the details.
+// The derived map form is used by `EntryVisitor`, which also accepts the plain text.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(remote = "Self", deny_unknown_fields)]
+pub struct Entry {
+    pub text: String,
+    // Breaking marks a change that needs the users to modify their code. Defaults to true for breaking and removed entries.
+    pub breaking: Option<bool>,
+    // Migration describes how to upgrade past a breaking, removed or deprecated change. May contain code blocks.
+    pub migration: Option<String>,
+    // Api identifies the function, type or feature the entry is about, e.g. `Client::poll`.
+    // A deprecated entry is linked to the later removed entry with the same api, and an experimental entry
+    // to the later added (stabilized) or removed (dropped) entry.
+    pub api: Option<String>,
+    // Id identifies a workaround or a known issue (`noted`), so a later fixed or improved entry can resolve it.
+    pub id: Option<String>,
+    // Resolves is the id of the workaround or known issue that this entry fixes.
+    pub resolves: Option<String>,
+    // Advisory is the published security advisory of a security entry.
+    pub advisory: Option<Advisory>,
+}
+
+impl From<String> for Entry {
+    fn from(text: String) -> Self {
+        Entry {
+            text,
+            ..Entry::default()
+        }
+    }
+}
+
+impl Entry {
+    pub fn is_breaking(&self, category: CategoryType) -> bool {
+        self.breaking
+            .unwrap_or(matches!(category, CategoryType::Breaking | CategoryType::Removed))
+    }
+
+    /// Breaking and deprecated entries are the ones that can have a migration note.
+    pub fn can_have_migration(&self, category: CategoryType) -> bool {
+        self.is_breaking(category) || category == CategoryType::Deprecated
+    }
+}
+
+struct EntryVisitor;
+
+// Plain scalars like `3` or `true` are entry texts too, as they were when entries were only strings.
+impl<'de> Visitor<'de> for EntryVisitor {
+    type Value = Entry;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an entry text, or a map with `text` and optional details")
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<Entry, E> {
+        Ok(Entry::from(value.to_string()))
+    }
+
+    fn visit_bool<E: Error>(self, value: bool) -> Result<Entry, E> {
+        Ok(Entry::from(value.to_string()))
+    }
+
+    fn visit_i64<E: Error>(self, value: i64) -> Result<Entry, E> {
+        Ok(Entry::from(value.to_string()))
+    }
+
+    fn visit_u64<E: Error>(self, value: u64) -> Result<Entry, E> {
+        Ok(Entry::from(value.to_string()))
+    }
+
+    fn visit_f64<E: Error>(self, value: f64) -> Result<Entry, E> {
+        Ok(Entry::from(value.to_string()))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, access: A) -> Result<Entry, A::Error> {
+        Entry::deserialize(MapAccessDeserializer::new(access))
+    }
+}
+
+impl<'de> Deserialize<'de> for Entry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Entry, D::Error> {
+        deserializer.deserialize_any(EntryVisitor)
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Deserialize)]
 pub struct Changes {
     // Added denotes new features or functionalities introduced in the software.
-    pub added: Option<Vec<String>>,
+    pub added: Option<Vec<Entry>>,
 
     // Changed indicates changes to existing features or functionalities.
-    pub changed: Option<Vec<String>>,
+    pub changed: Option<Vec<Entry>>,
 
     // Deprecated signifies functionalities that are no longer recommended and will be removed in future versions.
-    pub deprecated: Option<Vec<String>>,
+    pub deprecated: Option<Vec<Entry>>,
 
     // Removed lists functionalities or features that have been removed from the software. Should have been set as Deprecated in version prior to being removed. Is implicitly breaking changes.
-    pub removed: Option<Vec<String>>,
+    pub removed: Option<Vec<Entry>>,
 
     // Fixed enumerates fixes for bugs or issues in the software.
-    pub fixed: Option<Vec<String>>,
+    pub fixed: Option<Vec<Entry>>,
 
     // Security includes changes related to security enhancements or fixes.
-    pub security: Option<Vec<String>>,
+    pub security: Option<Vec<Entry>>,
 
     // ---------------- Others ---------------
 
     // Improved lists improvements made to existing functionalities without adding new features.
-    pub improved: Option<Vec<String>>,
+    pub improved: Option<Vec<Entry>>,
 
     // Workaround provides workarounds or temporary solutions for known issues or limitations.
-    pub workaround: Option<Vec<String>>,
+    pub workaround: Option<Vec<Entry>>,
 
     // Tests includes changes or additions to testing procedures or test cases.
-    pub tests: Option<Vec<String>>,
+    pub tests: Option<Vec<Entry>>,
 
     // Docs lists changes or additions to documentation, such as README files or inline code comments.
-    pub docs: Option<Vec<String>>,
+    pub docs: Option<Vec<Entry>>,
 
     // Refactored denotes changes made to improve code structure or organization without changing external behavior.
-    pub refactored: Option<Vec<String>>,
+    pub refactored: Option<Vec<Entry>>,
 
     // Optimization includes changes aimed at improving the performance of the software.
-    pub optimization: Option<Vec<String>>,
+    pub optimization: Option<Vec<Entry>>,
 
     // Breaking denotes changes that may break backward compatibility with previous versions. Changed, but breaks the API compatibilty.
-    pub breaking: Option<Vec<String>>,
+    pub breaking: Option<Vec<Entry>>,
 
     // Experimental lists experimental features or functionalities that are not yet stable or fully supported and might be removed with short or no notice in future versions.
-    pub experimental: Option<Vec<String>>,
+    pub experimental: Option<Vec<Entry>>,
 
     // Noted provides a place to note any other significant changes not covered by the above categories.
-    pub noted: Option<Vec<String>>,
+    pub noted: Option<Vec<Entry>>,
 
     // Style denotes changes related to coding style, formatting, or other stylistic aspects.
-    pub style: Option<Vec<String>>,
+    pub style: Option<Vec<Entry>>,
 
     // Unreleased contains a list of changes that are planned but not yet released in any version.
     // These changes typically represent work that is in progress or pending release in a future version.
     // Once a version is released, the changes listed in Unreleased are moved to the appropriate category (e.g., Added, Changed, Fixed, etc.).
-    pub unreleased: Option<Vec<String>>,
+    pub unreleased: Option<Vec<Entry>>,
+
+    pub examples: Option<Vec<Entry>>,
+
+    // Yanked marks a package release that was pulled from the registry. Only used for the packages of a release.
+    pub yanked: Option<Yanked>,
+}
+
+impl Changes {
+    /// All categories in the order they are rendered, with the entries of each category.
+    pub fn categories(&self) -> Vec<(CategoryType, &Option<Vec<Entry>>)> {
+        vec![
+            (CategoryType::Unreleased, &self.unreleased),
+            (CategoryType::Breaking, &self.breaking),
+            (CategoryType::Added, &self.added),
+            (CategoryType::Fixed, &self.fixed),
+            (CategoryType::Workaround, &self.workaround),
+            (CategoryType::Changed, &self.changed),
+            (CategoryType::Removed, &self.removed),
+            (CategoryType::Improved, &self.improved),
+            (CategoryType::Docs, &self.docs),
+            (CategoryType::Tests, &self.tests),
+            (CategoryType::Refactored, &self.refactored),
+            (CategoryType::Deprecated, &self.deprecated),
+            (CategoryType::Experimental, &self.experimental),
+            (CategoryType::Noted, &self.noted),
+            (CategoryType::Optimization, &self.optimization),
+            (CategoryType::Style, &self.style),
+            (CategoryType::Security, &self.security),
+            (CategoryType::Examples, &self.examples),
+        ]
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (CategoryType, &Entry)> {
+        self.categories()
+            .into_iter()
+            .flat_map(|(category, list)| list.iter().flatten().map(move |entry| (category, entry)))
+    }
+}
 
-    pub examples: Option<Vec<String>>,
+/// A pulled release, either `yanked: true` or the reason, e.g. `yanked: corrupt save files`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Yanked {
+    Flag(bool),
+    Reason(String),
+}
+
+impl Yanked {
+    pub fn is_yanked(&self) -> bool {
+        !matches!(self, Yanked::Flag(false))
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        match self {
+            Yanked::Reason(reason) => Some(reason),
+            Yanked::Flag(_) => None,
+        }
+    }
+}
+
+/// True if the optional yanked flag is set.
+pub fn is_yanked(yanked: &Option<Yanked>) -> bool {
+    yanked.as_ref().is_some_and(Yanked::is_yanked)
 }
 
 #[derive(Debug, PartialEq, Deserialize)]
@@ -75,23 +235,141 @@ pub struct Section {
 
 #[derive(Debug, PartialEq, Deserialize)]
 pub struct Release {
-    pub date: String,
+    // Date is an ISO-8601 calendar date (YYYY-MM-DD). Only unreleased versions may omit it.
+    #[serde(default, deserialize_with = "deserialize_optional_date")]
+    pub date: Option<NaiveDate>,
     pub notice: Option<String>,
     pub repos: Option<IndexMap<String, Changes>>,
     pub sections: Option<IndexMap<String, Section>>,
     pub packages: Option<IndexMap<String, Changes>>,
+    // Yanked marks a release that was pulled, e.g. because it was broken. It is still listed, but crossed out.
+    pub yanked: Option<Yanked>,
+    // Assets are the downloadable files of the release.
+    pub assets: Option<Vec<Asset>>,
+}
+
+/// Where a set of changes is listed within a release.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Scope<'a> {
+    Section(&'a str),
+    Package(&'a str),
+    Repo(&'a str),
+}
+
+impl<'a> Scope<'a> {
+    /// `section`, `package` or `repo`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Scope::Section(_) => "section",
+            Scope::Package(_) => "package",
+            Scope::Repo(_) => "repo",
+        }
+    }
+
+    pub fn name(&self) -> &'a str {
+        match self {
+            Scope::Section(name) | Scope::Package(name) | Scope::Repo(name) => name,
+        }
+    }
+}
+
+impl fmt::Display for Scope<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.kind(), self.name())
+    }
+}
+
+impl Release {
+    /// All changes in the release, in the order they are rendered.
+    pub fn scoped_changes(&self) -> Vec<(Scope<'_>, &Changes)> {
+        let mut result = Vec::new();
+        for (name, section) in self.sections.iter().flatten() {
+            result.push((Scope::Section(name.as_str()), &section.changes));
+        }
+        for (name, changes) in self.packages.iter().flatten() {
+            result.push((Scope::Package(name.as_str()), changes));
+        }
+        for (name, changes) in self.repos.iter().flatten() {
+            result.push((Scope::Repo(name.as_str()), changes));
+        }
+        result
+    }
 }
 
 #[derive(Debug, PartialEq, Deserialize)]
 pub struct DependencyRepoInfo {
     pub repo: String,
+    // Forge is where the repository is hosted, if not on the same forge as the document.
+    pub forge: Option<Forge>,
     pub name: String,
     pub description: String,
 }
 
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct PackageInfo {
+    // Tag is the tag name template for the releases of the package, e.g. `nimble-lib-{version}`.
+    pub tag: Option<String>,
+}
+
 #[derive(Debug, PartialEq, Deserialize)]
 pub struct Document {
     pub repo: String,
+    // Tag is the tag name template for the releases, e.g. `release/{version}`. Defaults to `{version}`.
+    pub tag: Option<String>,
+    // Forge is where the repository is hosted. Defaults to GitHub.
+    pub forge: Option<Forge>,
+    // Urls replaces individual url templates of the forge.
+    pub urls: Option<UrlTemplateOverrides>,
+    // Autolinks are references to external issue trackers, e.g. `NET-1234` for Jira.
+    pub autolinks: Option<Vec<Autolink>>,
+    pub packages: Option<HashMap<String, PackageInfo>>,
+    // Versioning decides how the release keys are parsed and ordered. Defaults to SemVer.
+    pub versioning: Option<VersionScheme>,
+    // Support lists the release lines, e.g. `2` or `1.4`, with their LTS status and end of life.
+    pub support: Option<IndexMap<String, SupportLine>>,
+    #[serde(deserialize_with = "deserialize_unique_keys")]
     pub releases: IndexMap<String, Release>,
     pub repos: Option<HashMap<String, DependencyRepoInfo>>,
 }
+
+impl Document {
+    /// The newest released version. Yanked releases are skipped unless `include_yanked` is set.
+    pub fn latest_release(&self, include_yanked: bool) -> Option<&String> {
+        self.releases
+            .iter()
+            .find(|(name, release)| !is_unreleased(name) && (include_yanked || !is_yanked(&release.yanked)))
+            .map(|(name, _)| name)
+    }
+}
+
+struct UniqueKeysVisitor<V> {
+    marker: PhantomData<V>,
+}
+
+impl<'de, V: Deserialize<'de>> Visitor<'de> for UniqueKeysVisitor<V> {
+    type Value = IndexMap<String, V>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map with unique keys")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut map = IndexMap::new();
+        while let Some((key, value)) = access.next_entry::<String, V>()? {
+            if map.contains_key(&key) {
+                return Err(A::Error::custom(format!("duplicate release '{}'", key)));
+            }
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
+}
+
+// serde_yaml silently keeps the last entry for duplicated keys, which would hide a release.
+fn deserialize_unique_keys<'de, D, V>(deserializer: D) -> Result<IndexMap<String, V>, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    deserializer.deserialize_map(UniqueKeysVisitor { marker: PhantomData })
+}